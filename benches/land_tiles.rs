@@ -0,0 +1,202 @@
+//! Tile removal and restore on a 256x256 grid, alone and as a whole frame with the tile visuals.
+//!
+//! Frames run the tile systems like the game does: fade, show up and restore cue animations of
+//! the [`TileVisual`](ground_breaking::tilemap::TileVisual)s in `Update`, chunk meshes rebuilt in
+//! `PostUpdate`. Autotiling is left out, it only runs when tiles change and needs a loaded tileset.
+//!
+//! Run with `cargo bench --bench land_tiles`. Uses a plain timing loop instead of a benchmark
+//! framework, so it runs on stable without extra dependencies.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    ecs::{schedule::ScheduleLabel, system::RunSystemOnce},
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use bevy_consumable_event::{ConsumableEventApp, ConsumableEvents};
+use ground_breaking::{
+    camera::ZoomPulse,
+    dynamic_initialization::DynamicInitializationPlugin,
+    playing_state::{remove_tiles, tick_and_restore_tiles, LandTiles, RemoveTile, TileChanged},
+    tilemap::{rebuild_dirty_chunks, setup_tiles, TileChunks},
+    tuning::Tuning,
+};
+
+const GRID_SIZE: u32 = 256;
+const TILE_SIZE: f32 = 40.;
+const FRAME: Duration = Duration::from_millis(16);
+const ITERATIONS: u32 = 200;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct Remove;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct Restore;
+
+/// App with tile visuals and the tile systems, both in their own schedules and in the frame,
+/// already initialized
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, DynamicInitializationPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME))
+        .insert_resource(LandTiles::new(UVec2::splat(GRID_SIZE), TILE_SIZE))
+        .init_resource::<Tuning>()
+        .init_resource::<TileChunks>()
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_consumable_event::<RemoveTile>()
+        .add_consumable_event::<ZoomPulse>()
+        .add_event::<TileChanged>()
+        .add_systems(Remove, remove_tiles)
+        .add_systems(Restore, tick_and_restore_tiles)
+        .add_systems(Update, (remove_tiles, tick_and_restore_tiles))
+        .add_systems(PostUpdate, rebuild_dirty_chunks);
+
+    app.world_mut().run_system_once(setup_tiles);
+    // Advances `Time`, so timers see a non zero delta, adds the animation systems of the visuals
+    // and builds the chunks for the first time
+    app.update();
+    app.update();
+
+    let world = app.world_mut();
+    world.run_schedule(Remove);
+    world.run_schedule(Restore);
+    app
+}
+
+/// Centers of every `step`-th cell, row after row
+fn cells(step: usize) -> Vec<Vec2> {
+    let tiles = LandTiles::new(UVec2::splat(GRID_SIZE), TILE_SIZE);
+
+    (0..GRID_SIZE as i32)
+        .flat_map(|x| (0..GRID_SIZE as i32).map(move |y| IVec2::new(x, y)))
+        .step_by(step)
+        .map(|cell| tiles.array_to_world(cell))
+        .collect()
+}
+
+fn send_remove(app: &mut App, positions: &[Vec2], duration: Duration) {
+    app.world_mut()
+        .resource_mut::<ConsumableEvents<RemoveTile>>()
+        .send_batch(positions.iter().map(|&pos| RemoveTile(pos, duration)));
+}
+
+/// Runs `routine` on a fresh app from `setup` each iteration and prints the mean time
+fn measure(name: &str, mut setup: impl FnMut() -> App, mut routine: impl FnMut(&mut App)) {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let mut app = setup();
+
+        let start = Instant::now();
+        routine(black_box(&mut app));
+        total += start.elapsed();
+    }
+
+    println!("{name:<40} {:>12.2?}", total / ITERATIONS);
+}
+
+fn bench_remove_tiles() {
+    for (name, step) in [
+        ("remove_tiles/1%", 100),
+        ("remove_tiles/10%", 10),
+        ("remove_tiles/100%", 1),
+    ] {
+        let positions = cells(step);
+
+        measure(
+            name,
+            || {
+                let mut app = app();
+                send_remove(&mut app, &positions, Duration::from_secs(60));
+                app
+            },
+            |app| app.world_mut().run_schedule(Remove),
+        );
+    }
+}
+
+fn bench_tick_and_restore_tiles() {
+    for (name, step, duration) in [
+        ("tick_and_restore_tiles/0%", None, Duration::ZERO),
+        (
+            "tick_and_restore_tiles/5% ticking",
+            Some(20),
+            Duration::from_secs(60),
+        ),
+        (
+            "tick_and_restore_tiles/5% restoring",
+            Some(20),
+            Duration::ZERO,
+        ),
+        (
+            "tick_and_restore_tiles/100% ticking",
+            Some(1),
+            Duration::from_secs(60),
+        ),
+    ] {
+        let positions = step.map(cells).unwrap_or_default();
+
+        measure(
+            name,
+            || {
+                let mut app = app();
+                send_remove(&mut app, &positions, duration);
+                app.world_mut().run_schedule(Remove);
+                app
+            },
+            |app| app.world_mut().run_schedule(Restore),
+        );
+    }
+}
+
+/// Runs frames on one app prepared by `setup` and prints the mean time of a frame.
+/// `setup` must leave the app in a state that frames don't change much.
+fn measure_frames(name: &str, setup: impl FnOnce(&mut App)) {
+    let mut app = app();
+    setup(&mut app);
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(&mut app).update();
+    }
+
+    println!("{name:<40} {:>12.2?}", start.elapsed() / ITERATIONS);
+}
+
+fn bench_frame() {
+    // Restore time is longer than all measured frames, so destroyed tiles only tick and animate
+    // their restore cue. Fade away animations are over after `settle`
+    let settle = |app: &mut App| {
+        for _ in 0..30 {
+            app.update();
+        }
+    };
+
+    measure_frames("frame/idle", |_| {});
+
+    for (name, step) in [
+        ("frame/1% destroyed", 100),
+        ("frame/5% destroyed", 20),
+        ("frame/100% destroyed", 1),
+    ] {
+        let positions = cells(step);
+
+        measure_frames(name, |app| {
+            // Events sent outside of a frame are cleared before `Update`
+            send_remove(app, &positions, Duration::from_secs(60));
+            app.world_mut().run_schedule(Remove);
+            settle(app);
+        });
+    }
+}
+
+fn main() {
+    bench_remove_tiles();
+    bench_tick_and_restore_tiles();
+    bench_frame();
+}
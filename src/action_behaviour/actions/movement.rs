@@ -7,19 +7,59 @@ pub(super) fn register_movement_action(app: &mut App) {
     app.register_action::<MovementAction>();
 }
 
+/// Rates of velocity change in units per second squared
+#[derive(Reflect, Clone, Copy, Debug)]
+pub struct MovementParams {
+    /// Speeding up towards the direction
+    pub acceleration: f32,
+    /// Slowing down when turning around or going faster than max speed
+    pub deceleration: f32,
+    /// Slowing down without direction
+    pub friction: f32,
+}
+
 #[derive(Component)]
 pub struct MovementAction {
     pub direction: Vec2,
     pub max_speed: f32,
-    pub acceleration: f32,
+    pub ground: MovementParams,
+    /// Used while the entity is [`airborne`](MovementAction::airborne)
+    pub airborne_params: MovementParams,
+    /// Entity isn't standing on the ground, for example falling into a hole
+    pub airborne: bool,
+    /// Scales `max_speed`, set by slow [`StatusEffect`](crate::status_effects::StatusEffect)
+    pub speed_multiplier: f32,
+    /// Velocity used instead of steering, set by knockback
+    pub override_velocity: Option<Vec2>,
 }
 
 impl MovementAction {
-    pub fn new(max_speed: f32, acceleration: f32) -> Self {
+    /// Uses `ground` params for airborne movement too
+    pub fn new(max_speed: f32, ground: MovementParams) -> Self {
         Self {
             direction: Vec2::ZERO,
             max_speed,
-            acceleration,
+            ground,
+            airborne_params: ground,
+            airborne: false,
+            speed_multiplier: 1.0,
+            override_velocity: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_airborne(mut self, airborne: MovementParams) -> Self {
+        self.airborne_params = airborne;
+        self
+    }
+
+    /// Params for the current state
+    #[inline]
+    pub fn params(&self) -> &MovementParams {
+        if self.airborne {
+            &self.airborne_params
+        } else {
+            &self.ground
         }
     }
 }
@@ -30,11 +70,90 @@ impl Action for MovementAction {
     }
 }
 
-fn apply_movement(mut query: Query<(&mut LinearVelocity, &MovementAction)>) {
+/// Moves `velocity` towards `target` for `delta_seconds`.
+/// Velocity changes linearly, so time to reach the target doesn't depend on the timestep.
+pub fn steer(velocity: Vec2, target: Vec2, params: &MovementParams, delta_seconds: f32) -> Vec2 {
+    let rate = if target == Vec2::ZERO {
+        params.friction
+    } else if velocity.dot(target) < 0. || velocity.length_squared() > target.length_squared() {
+        params.deceleration
+    } else {
+        params.acceleration
+    };
+
+    let difference = target - velocity;
+    let max_change = rate * delta_seconds;
+
+    if difference.length() <= max_change {
+        target
+    } else {
+        velocity + difference.normalize() * max_change
+    }
+}
+
+fn apply_movement(time: Res<Time>, mut query: Query<(&mut LinearVelocity, &MovementAction)>) {
     for (mut velocity, movement) in query.iter_mut() {
-        let target = movement.direction * movement.max_speed;
+        if let Some(override_velocity) = movement.override_velocity {
+            velocity.0 = override_velocity;
+            continue;
+        }
+
+        let target = movement.direction * movement.max_speed * movement.speed_multiplier;
 
-        velocity.0 = velocity.0.lerp(target, movement.acceleration);
-        // info!("Velocity: {}", velocity.0);
+        velocity.0 = steer(velocity.0, target, movement.params(), time.delta_seconds());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: MovementParams = MovementParams {
+        acceleration: 800.,
+        deceleration: 1600.,
+        friction: 400.,
+    };
+    const MAX_SPEED: f32 = 200.;
+    /// Fixed timestep rates in Hz
+    const RATES: [f32; 4] = [30., 60., 64., 144.];
+
+    /// Seconds `steer` takes to get from `velocity` to `target` at `rate` steps per second
+    fn time_to_reach(mut velocity: Vec2, target: Vec2, rate: f32) -> f32 {
+        let delta_seconds = 1. / rate;
+        let mut steps = 0;
+
+        while velocity != target {
+            velocity = steer(velocity, target, &PARAMS, delta_seconds);
+            steps += 1;
+            assert!(steps < 10_000, "never reached {target} at {rate} Hz");
+        }
+
+        steps as f32 * delta_seconds
+    }
+
+    #[test]
+    fn time_to_max_speed_does_not_depend_on_timestep() {
+        let expected = MAX_SPEED / PARAMS.acceleration;
+
+        for rate in RATES {
+            let time = time_to_reach(Vec2::ZERO, Vec2::X * MAX_SPEED, rate);
+            assert!(
+                (time - expected).abs() <= 1. / rate + 1e-4,
+                "{time}s to max speed at {rate} Hz, expected {expected}s"
+            );
+        }
+    }
+
+    #[test]
+    fn stop_time_does_not_depend_on_timestep() {
+        let expected = MAX_SPEED / PARAMS.friction;
+
+        for rate in RATES {
+            let time = time_to_reach(Vec2::X * MAX_SPEED, Vec2::ZERO, rate);
+            assert!(
+                (time - expected).abs() <= 1. / rate + 1e-4,
+                "{time}s to stop at {rate} Hz, expected {expected}s"
+            );
+        }
     }
 }
@@ -0,0 +1,42 @@
+//! Enemies walking towards the player around holes, see [`Navigation`].
+
+use bevy::prelude::*;
+
+use crate::{
+    action_behaviour::{actions::movement::MovementAction, ActionBehaviourApp, Behaviour},
+    navigation::Navigation,
+    status_effects::Stunned,
+};
+
+pub(super) fn register_chase_player_behaviour(app: &mut App) {
+    app.register_behaviour::<ChasePlayerBehaviour>();
+}
+
+/// Walks to the next waypoint towards the player, stands still in the player's cell
+/// or when the player can't be reached
+#[derive(Component)]
+pub struct ChasePlayerBehaviour;
+
+impl Behaviour for ChasePlayerBehaviour {
+    fn systems() -> bevy::ecs::schedule::SystemConfigs {
+        chase_player.into_configs()
+    }
+}
+
+fn chase_player(
+    mut query: Query<
+        (&Transform, &mut MovementAction),
+        (With<ChasePlayerBehaviour>, Without<Stunned>),
+    >,
+    navigation: Navigation,
+) {
+    for (transform, mut movement) in query.iter_mut() {
+        let position = transform.translation.xy();
+
+        movement.direction = navigation
+            .next_waypoint(position)
+            .map_or(Vec2::ZERO, |waypoint| {
+                (waypoint - position).normalize_or_zero()
+            });
+    }
+}
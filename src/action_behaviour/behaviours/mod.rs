@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+pub mod chase_player;
 pub mod player;
 
 pub struct RegisterBehaviours;
@@ -7,5 +8,6 @@ pub struct RegisterBehaviours;
 impl Plugin for RegisterBehaviours {
     fn build(&self, app: &mut App) {
         player::register_player_behvaiour(app);
+        chase_player::register_chase_player_behaviour(app);
     }
 }
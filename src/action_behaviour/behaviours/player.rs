@@ -1,12 +1,17 @@
-use std::time::Duration;
-
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::Duration};
 use bevy_consumable_event::ConsumableEventWriter;
 
 use crate::{
-    action_behaviour::{actions::movement::MovementAction, ActionBehaviourApp, Behaviour},
+    action_behaviour::{
+        actions::{emit_projectile::EmitProjectile, movement::MovementAction},
+        ActionBehaviourApp, Behaviour,
+    },
+    actors::{lifecycle::Despawning, player::stomp::PlayerStomp},
+    common::frame_animation::{clips, FrameAnimation, FrameClips},
     input_map::InputMap,
     playing_state::RemoveTile,
+    status_effects::Stunned,
+    tuning::Tuning,
 };
 
 pub(super) fn register_player_behvaiour(app: &mut App) {
@@ -16,8 +21,6 @@ pub(super) fn register_player_behvaiour(app: &mut App) {
 #[derive(Component)]
 pub struct PlayerBehaviour;
 
-const REMOVE_TILE_DURATION: Duration = Duration::from_millis(2000);
-
 impl Behaviour for PlayerBehaviour {
     fn systems() -> bevy::ecs::schedule::SystemConfigs {
         (player_movement, player_tile_destruction).into_configs()
@@ -25,23 +28,55 @@ impl Behaviour for PlayerBehaviour {
 }
 
 fn player_movement(
-    mut query: Query<&mut MovementAction, With<PlayerBehaviour>>,
+    mut query: Query<
+        (
+            &mut MovementAction,
+            Option<&mut FrameAnimation>,
+            Has<Despawning>,
+        ),
+        (With<PlayerBehaviour>, Without<Stunned>),
+    >,
     input: Res<InputMap>,
+    frame_clips: Res<Assets<FrameClips>>,
 ) {
-    for mut movement in query.iter_mut() {
+    for (mut movement, animation, despawning) in query.iter_mut() {
         movement.direction = input.movement_direction();
+
+        // Falling and other one-shot clips aren't interrupted by walking
+        let animation =
+            animation.filter(|animation| !despawning && !animation.playing_once(&frame_clips));
+
+        if let Some(mut animation) = animation {
+            if movement.direction == Vec2::ZERO {
+                animation.play(clips::IDLE);
+            } else {
+                animation.play(clips::WALK);
+            }
+        }
     }
 }
 
+/// Destroys the tile under the player and stomps, knocking enemies around away
 fn player_tile_destruction(
-    query: Query<&Transform, With<PlayerBehaviour>>,
+    mut query: Query<
+        (&Transform, Option<&mut EmitProjectile<PlayerStomp>>),
+        (With<PlayerBehaviour>, Without<Stunned>),
+    >,
     mut input: ResMut<InputMap>,
+    tuning: Res<Tuning>,
     mut event: ConsumableEventWriter<RemoveTile>,
 ) {
-    for transform in query.iter() {
+    for (transform, emit_stomp) in query.iter_mut() {
         if input.destroy_tile() {
             let pos = transform.translation.xy();
-            event.send(RemoveTile(pos, REMOVE_TILE_DURATION));
+            event.send(RemoveTile(
+                pos,
+                Duration::from_secs_f32(tuning.player.remove_tile_duration),
+            ));
+
+            if let Some(mut emit_stomp) = emit_stomp {
+                emit_stomp.emit(PlayerStomp { position: pos });
+            }
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::GameState;
+use crate::{introspection::RegisteredTypes, GameState};
 use bevy::{
     ecs::schedule::{ScheduleLabel, SystemConfigs},
     prelude::*,
@@ -22,6 +22,7 @@ impl Plugin for ActionBehaviourPlugin {
     }
 }
 
+/// Systems of behaviours should skip entities with [`Stunned`](crate::status_effects::Stunned)
 pub trait Behaviour: Component {
     fn systems() -> SystemConfigs;
 }
@@ -30,8 +31,10 @@ pub trait Action: Component {
     fn systems() -> SystemConfigs;
 }
 
+/// Schedule in which [`ActionSet`] and [`BehaviourSet`] are configured.
+/// Use it when adding actions or behaviours with [`AddSystemsTo`](crate::dynamic_initialization::AddSystemsTo).
 #[inline(always)]
-fn action_behaviour_schedule() -> impl ScheduleLabel {
+pub fn action_behaviour_schedule() -> impl ScheduleLabel {
     FixedUpdate
 }
 
@@ -48,6 +51,10 @@ pub trait ActionBehaviourApp {
 impl ActionBehaviourApp for App {
     #[inline]
     fn register_behaviour<B: Behaviour>(&mut self) -> &mut Self {
+        RegisteredTypes::get(self)
+            .behaviours
+            .push(std::any::type_name::<B>());
+
         self.add_systems(
             action_behaviour_schedule(),
             B::systems().in_set(BehaviourSet),
@@ -56,6 +63,10 @@ impl ActionBehaviourApp for App {
 
     #[inline]
     fn register_action<A: Action>(&mut self) -> &mut Self {
+        RegisteredTypes::get(self)
+            .actions
+            .push(std::any::type_name::<A>());
+
         self.add_systems(action_behaviour_schedule(), A::systems().in_set(ActionSet))
     }
 }
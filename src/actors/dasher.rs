@@ -1,31 +1,46 @@
 use crate::{
     action_behaviour::actions::emit_projectile::EmitProjectile,
+    camera::AddTrauma,
     common::{
-        colliders::{Alignment, CollidersCommands},
+        colliders::Alignment,
+        frame_animation::{clips, FrameAnimation},
+        hits::Knockback,
+        run_on_frame_event::RunOnFrameEvent,
         run_on_timer::RunOnTimer,
-        CommonEntityCommands,
+        walls::StoppedByWalls,
     },
     dynamic_initialization::{DataItem, EntitySystem, ParamItem},
+    tuning::Tuning,
 };
 
-use super::{Actor, AppRegisteringActors};
+use super::{
+    lifecycle::{HitboxProjectile, PooledActor},
+    Actor, AppRegisteringActors,
+};
 use avian2d::prelude::*;
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle, utils::Duration};
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::Duration,
+};
+use bevy_consumable_event::ConsumableEventWriter;
 
 pub struct RegisterDasher;
 
 impl Plugin for RegisterDasher {
     fn build(&self, app: &mut App) {
-        app.register_actor::<Dasher>()
-            .register_actor::<DasherAttack>();
+        app.register_pooled_actor::<DasherAttack>().add_systems(
+            Update,
+            apply_dasher_tuning.run_if(resource_changed::<Tuning>),
+        );
     }
 }
 
 const ATTACK_COLOR: Color = Color::linear_rgb(1., 0., 0.);
-const ATTACK_LIFETIME: Duration = Duration::from_millis(500); // 0.5 secs
-const ATTACK_HITBOX_LIFETIME: Duration = Duration::from_millis(75); // 0.075 secs
-const DASHER_ATTACK_RADIUS: f32 = 20.0;
-const DASHER_ATTACK_PERIOD: Duration = Duration::from_secs(3);
+
+/// Frame event on which [`DasherAttack`] is spawned
+const DASHER_IMPACT_EVENT: &str = "impact";
 
 struct DasherAttack {
     position: Vec2,
@@ -37,73 +52,149 @@ impl Actor for DasherAttack {
         Commands<'static, 'static>,
         ResMut<'static, Assets<Mesh>>,
         ResMut<'static, Assets<ColorMaterial>>,
+        Res<'static, Tuning>,
+        Query<'static, 'static, (&'static Mesh2dHandle, &'static Handle<ColorMaterial>)>,
     );
 
-    fn spawn(self, param: <Self::Param as bevy::ecs::system::SystemParam>::Item<'_, '_>) {
-        let (mut commands, mut meshes, mut materials) = param;
-
-        commands
-            .spawn((MaterialMesh2dBundle {
-                mesh: meshes.add(Circle::new(self.radius)).into(),
-                material: materials.add(ColorMaterial::from_color(ATTACK_COLOR)),
-                transform: Transform::from_translation(self.position.extend(0.0)),
-                ..default()
-            },))
-            .with_hitbox_for(
-                Alignment::Enemy,
-                Collider::circle(self.radius),
-                ATTACK_HITBOX_LIFETIME,
-            )
-            .fade_away(ATTACK_LIFETIME);
+    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) -> Entity {
+        let (mut commands, mut meshes, mut materials, tuning, _) = param;
+
+        let mut entity = commands.spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(Circle::new(self.radius)).into(),
+            material: materials.add(ColorMaterial::from_color(ATTACK_COLOR)),
+            ..default()
+        });
+        self.activate(&mut entity, &tuning);
+
+        entity.id()
     }
 }
 
+impl PooledActor for DasherAttack {
+    fn respawn(self, entity: Entity, param: <Self::Param as SystemParam>::Item<'_, '_>) {
+        let (mut commands, mut meshes, mut materials, tuning, attacks) = param;
+
+        if let Ok((mesh, material)) = attacks.get(entity) {
+            meshes.insert(&mesh.0, Circle::new(self.radius).into());
+            if let Some(material) = materials.get_mut(material) {
+                material.color = ATTACK_COLOR;
+            }
+        }
+
+        self.activate(&mut commands.entity(entity), &tuning);
+    }
+}
+
+impl DasherAttack {
+    /// Parts shared by fresh and reused attacks
+    fn activate(self, entity: &mut EntityCommands, tuning: &Tuning) {
+        if tuning.dasher.attack_stopped_by_walls {
+            entity.insert(StoppedByWalls);
+        } else {
+            entity.remove::<StoppedByWalls>();
+        }
+
+        HitboxProjectile {
+            position: self.position,
+            alignment: Alignment::Enemy,
+            hitbox: Collider::circle(self.radius),
+            knockback: Knockback {
+                strength: tuning.dasher.knockback_strength,
+                duration: Duration::from_secs_f32(tuning.dasher.knockback_duration),
+            },
+            lifetime: Duration::from_secs_f32(tuning.dasher.attack_lifetime),
+            hitbox_lifetime: Duration::from_secs_f32(tuning.dasher.attack_hitbox_lifetime),
+        }
+        .activate(entity);
+    }
+}
+
+/// Components making the entity attack around itself every `period`.
+/// Entities with [`FrameAnimation`] wind up with [`clips::ATTACK_WINDUP`] first,
+/// others attack right away.
+///
+/// `phase` in `0.0..1.0` is the part of the first period that has already passed,
+/// so dashers spawned together don't attack in sync.
+pub(crate) fn dasher_attack(period: Duration, phase: f32) -> impl Bundle {
+    let mut timer = Timer::new(period, TimerMode::Repeating);
+    timer.set_elapsed(period.mul_f32(phase));
+
+    (
+        EmitProjectile::<DasherAttack>::default(),
+        RunOnTimer::<DasherPeriodicAction>::new(timer),
+        RunOnFrameEvent::<DasherImpact>::new(DASHER_IMPACT_EVENT),
+    )
+}
+
 struct DasherPeriodicAction;
 
 impl EntitySystem for DasherPeriodicAction {
     type Data = (
+        Option<&'static mut FrameAnimation>,
         &'static mut EmitProjectile<DasherAttack>,
         &'static Transform,
     );
     type Filter = ();
-    type Param = ();
+    type Param = (
+        ConsumableEventWriter<'static, AddTrauma>,
+        Res<'static, Tuning>,
+    );
 
     type In = ();
     type Out = ();
 
     fn run(_: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
-        let (mut emit_projectile, transform) = data;
-        let () = param;
+        let (animation, emit_projectile, transform) = data;
 
-        emit_projectile.emit(DasherAttack {
-            position: transform.translation.xy(),
-            radius: DASHER_ATTACK_RADIUS,
-        });
+        match animation {
+            Some(mut animation) => animation.restart(clips::ATTACK_WINDUP),
+            None => impact(emit_projectile, transform, param),
+        }
     }
 }
 
-pub struct Dasher {
-    pub position: Vec2,
+struct DasherImpact;
+
+impl EntitySystem for DasherImpact {
+    type Data = (
+        &'static mut EmitProjectile<DasherAttack>,
+        &'static Transform,
+    );
+    type Filter = ();
+    type Param = (
+        ConsumableEventWriter<'static, AddTrauma>,
+        Res<'static, Tuning>,
+    );
+
+    type In = ();
+    type Out = ();
+
+    fn run(_: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (emit_projectile, transform) = data;
+
+        impact(emit_projectile, transform, param);
+    }
+}
+
+fn impact(
+    mut emit_projectile: Mut<EmitProjectile<DasherAttack>>,
+    transform: &Transform,
+    (mut trauma, tuning): (ConsumableEventWriter<AddTrauma>, Res<Tuning>),
+) {
+    emit_projectile.emit(DasherAttack {
+        position: transform.translation.xy(),
+        radius: tuning.dasher.attack_radius,
+    });
+    trauma.send(AddTrauma(tuning.dasher.impact_trauma));
 }
 
-impl Actor for Dasher {
-    type Param = (Commands<'static, 'static>, Res<'static, AssetServer>);
-    fn spawn(self, param: <Self::Param as bevy::ecs::system::SystemParam>::Item<'_, '_>) {
-        let (mut commands, asset_server) = param;
-
-        commands
-            .spawn((
-                SpriteBundle {
-                    transform: Transform::from_translation(self.position.extend(0.0)),
-                    texture: asset_server.load("textures/dasher.png"),
-                    ..default()
-                },
-                EmitProjectile::<DasherAttack>::default(),
-                RunOnTimer::<DasherPeriodicAction>::new(Timer::new(
-                    DASHER_ATTACK_PERIOD,
-                    TimerMode::Repeating,
-                )),
-            ))
-            .character_with_hurtbox(Alignment::Enemy, Collider::rectangle(12.0, 11.0));
+fn apply_dasher_tuning(
+    tuning: Res<Tuning>,
+    mut query: Query<&mut RunOnTimer<DasherPeriodicAction>>,
+) {
+    let period = Duration::from_secs_f32(tuning.dasher.attack_period);
+
+    for mut run_on_timer in query.iter_mut() {
+        run_on_timer.timer.set_duration(period);
     }
 }
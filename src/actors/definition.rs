@@ -0,0 +1,378 @@
+//! Actors described by data instead of code.
+//!
+//! `.actor.ron` files are loaded into [`ActorDefinition`] with
+//! [`ReflectRonLoader`](crate::common::reflect_ron::ReflectRonLoader):
+//!
+//! ```ron
+//! (
+//!     sprite: "textures/dasher.png",
+//!     sheet: Some((
+//!         frame_size: (x: 12, y: 11),
+//!         columns: 1,
+//!         rows: 1,
+//!         clips: [
+//!             (name: "idle", frames: [0], frame_duration: 0.1, looping: true, events: [], next: None),
+//!         ],
+//!     )),
+//!     collider: Rectangle(width: 12.0, height: 11.0),
+//!     alignment: Enemy,
+//!     weight: 2.0,
+//!     exit_duration: Some(0.3),
+//!     movement: None,
+//!     behaviour: None,
+//!     periodic_actions: [DasherAttack],
+//! )
+//! ```
+//!
+//! Spawned with [`SpawnActor<DataActor>`](super::SpawnActor) like any other actor.
+
+use avian2d::prelude::*;
+use bevy::{
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::{Duration, HashMap},
+};
+use rand::Rng;
+
+use super::{
+    dasher::dasher_attack, lifecycle::ExitAnimation, player::stomp::PlayerStomp,
+    spawn_telegraph::PlacedActor, Actor, AppRegisteringActors,
+};
+use crate::{
+    action_behaviour::{
+        actions::{
+            emit_projectile::EmitProjectile,
+            movement::{MovementAction, MovementParams},
+        },
+        behaviours::{chase_player::ChasePlayerBehaviour, player::PlayerBehaviour},
+    },
+    common::{
+        aseprite,
+        colliders::{Alignment, CollidersCommands},
+        frame_animation::{clips, FrameAnimation, FrameClip, FrameClips},
+        hits::Weight,
+        reflect_ron::{ReflectRonApp, ReflectRonAsset},
+        rng::{GameRng, RngStream},
+    },
+    tuning::Tuning,
+};
+
+pub struct RegisterDataActor;
+
+impl Plugin for RegisterDataActor {
+    fn build(&self, app: &mut App) {
+        app.init_reflect_ron_asset::<ActorDefinition>()
+            .register_actor::<DataActor>()
+            .register_telegraphed_spawn::<DataActor>()
+            .init_resource::<DefinitionSheets>()
+            .add_systems(
+                Update,
+                (create_definition_sheets, finish_data_actors).chain(),
+            );
+    }
+}
+
+#[derive(Asset, Reflect, Clone)]
+pub struct ActorDefinition {
+    /// Path to the texture. `.aseprite` files are animated with their clips
+    pub sprite: String,
+    /// Animates other textures, ignored for `.aseprite` files
+    pub sheet: Option<SpriteSheetDefinition>,
+    pub collider: ColliderDefinition,
+    pub alignment: Alignment,
+    /// See [`Weight`]
+    pub weight: f32,
+    /// Seconds the actor fades away for when despawned, see [`ExitAnimation`]
+    pub exit_duration: Option<f32>,
+    pub movement: Option<MovementDefinition>,
+    pub behaviour: Option<BehaviourDefinition>,
+    pub periodic_actions: Vec<PeriodicActionDefinition>,
+}
+
+impl ReflectRonAsset for ActorDefinition {
+    const EXTENSIONS: &'static [&'static str] = &["actor.ron"];
+}
+
+/// Texture split into a grid of frames
+#[derive(Reflect, Clone)]
+pub struct SpriteSheetDefinition {
+    /// In pixels
+    pub frame_size: UVec2,
+    pub columns: u32,
+    pub rows: u32,
+    /// Starts with [`clips::IDLE`]
+    pub clips: Vec<ClipDefinition>,
+}
+
+/// See [`FrameClip`]
+#[derive(Reflect, Clone)]
+pub struct ClipDefinition {
+    /// Behaviours switch to clips from [`clips`]
+    pub name: String,
+    /// Indices of the frames in the sheet
+    pub frames: Vec<usize>,
+    /// Seconds each frame is shown
+    pub frame_duration: f32,
+    pub looping: bool,
+    pub events: Vec<ClipEventDefinition>,
+    pub next: Option<String>,
+}
+
+/// [`FrameEvent`](crate::common::frame_animation::FrameEvent) sent when the frame is shown
+#[derive(Reflect, Clone)]
+pub struct ClipEventDefinition {
+    /// Position in [`ClipDefinition::frames`]
+    pub frame: usize,
+    pub event: String,
+}
+
+impl SpriteSheetDefinition {
+    fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.frame_size, self.columns, self.rows, None, None)
+    }
+
+    fn clips(&self) -> FrameClips {
+        let mut clips = FrameClips::default();
+
+        for definition in &self.clips {
+            let duration = Duration::from_secs_f32(definition.frame_duration);
+
+            let mut clip = FrameClip::new(definition.looping);
+            for (position, &index) in definition.frames.iter().enumerate() {
+                let event = definition
+                    .events
+                    .iter()
+                    .find(|event| event.frame == position);
+
+                clip = match event {
+                    Some(event) => clip.with_event_frame(index, duration, event.event.clone()),
+                    None => clip.with_frame(index, duration),
+                };
+            }
+            if let Some(next) = &definition.next {
+                clip = clip.then(next.clone());
+            }
+
+            clips.insert(definition.name.clone(), clip);
+        }
+
+        clips
+    }
+}
+
+#[derive(Reflect, Clone, Copy)]
+pub enum ColliderDefinition {
+    Circle { radius: f32 },
+    Rectangle { width: f32, height: f32 },
+}
+
+impl From<ColliderDefinition> for Collider {
+    fn from(definition: ColliderDefinition) -> Self {
+        match definition {
+            ColliderDefinition::Circle { radius } => Collider::circle(radius),
+            ColliderDefinition::Rectangle { width, height } => Collider::rectangle(width, height),
+        }
+    }
+}
+
+#[derive(Reflect, Clone, Copy)]
+pub struct MovementDefinition {
+    pub max_speed: f32,
+    pub ground: MovementParams,
+    /// Same as `ground` if not set
+    pub airborne: Option<MovementParams>,
+}
+
+#[derive(Reflect, Clone, Copy)]
+pub enum BehaviourDefinition {
+    /// [`PlayerBehaviour`], stomps with [`PlayerStomp`]
+    Player,
+    /// [`ChasePlayerBehaviour`], needs `movement`
+    ChasePlayer,
+}
+
+#[derive(Reflect, Clone, Copy)]
+pub enum PeriodicActionDefinition {
+    /// Attack of the dasher, tuned with [`DasherTuning`](crate::tuning::DasherTuning).
+    /// Actors with a sheet play [`clips::ATTACK_WINDUP`] and attack on it's `"impact"` event
+    /// frame, others attack right away
+    DasherAttack,
+}
+
+#[derive(Clone)]
+pub struct DataActor {
+    pub definition: Handle<ActorDefinition>,
+    pub position: Vec2,
+}
+
+/// [`DataActor`] which definition isn't loaded yet
+#[derive(Component)]
+struct PendingDataActor(Handle<ActorDefinition>);
+
+impl Actor for DataActor {
+    type Param = Commands<'static, 'static>;
+
+    /// Components from the definition are added once it's loaded,
+    /// so they may be missing in [`ActorSpawned`](super::lifecycle::ActorSpawned) handlers
+    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) -> Entity {
+        let mut commands = param;
+
+        commands
+            .spawn((
+                SpatialBundle::from_transform(Transform::from_translation(
+                    self.position.extend(0.0),
+                )),
+                PendingDataActor(self.definition),
+            ))
+            .id()
+    }
+}
+
+impl PlacedActor for DataActor {
+    #[inline]
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    #[inline]
+    fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+}
+
+/// Sheet assets of the loaded definitions, shared by all actors of the definition
+#[derive(Resource, Default)]
+struct DefinitionSheets(
+    HashMap<AssetId<ActorDefinition>, (Handle<TextureAtlasLayout>, Handle<FrameClips>)>,
+);
+
+fn create_definition_sheets(
+    mut events: EventReader<AssetEvent<ActorDefinition>>,
+    definitions: Res<Assets<ActorDefinition>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut frame_clips: ResMut<Assets<FrameClips>>,
+    mut sheets: ResMut<DefinitionSheets>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id }
+        | AssetEvent::Modified { id }
+        | AssetEvent::Removed { id }) = *event
+        else {
+            continue;
+        };
+
+        match definitions
+            .get(id)
+            .and_then(|definition| definition.sheet.as_ref())
+        {
+            Some(sheet) => {
+                sheets.0.insert(
+                    id,
+                    (layouts.add(sheet.layout()), frame_clips.add(sheet.clips())),
+                );
+            }
+            None => {
+                sheets.0.remove(&id);
+            }
+        }
+    }
+}
+
+fn finish_data_actors(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    definitions: Res<Assets<ActorDefinition>>,
+    sheets: Res<DefinitionSheets>,
+    tuning: Res<Tuning>,
+    mut rng: ResMut<GameRng>,
+    pending: Query<(Entity, &PendingDataActor, &Transform)>,
+) {
+    for (entity, PendingDataActor(handle), transform) in pending.iter() {
+        let Some(definition) = definitions.get(handle) else {
+            continue;
+        };
+        let sheet = sheets.0.get(&handle.id()).cloned();
+        if definition.sheet.is_some() && sheet.is_none() {
+            continue;
+        }
+
+        let mut entity = commands.entity(entity);
+        entity.remove::<PendingDataActor>();
+
+        let is_aseprite =
+            definition.sprite.ends_with(".aseprite") || definition.sprite.ends_with(".ase");
+        if is_aseprite {
+            let path = &definition.sprite;
+            entity.insert((
+                SpriteBundle {
+                    transform: *transform,
+                    texture: asset_server.load(format!("{path}#{}", aseprite::IMAGE_LABEL)),
+                    ..default()
+                },
+                TextureAtlas {
+                    layout: asset_server.load(format!("{path}#{}", aseprite::LAYOUT_LABEL)),
+                    index: 0,
+                },
+                FrameAnimation::new(
+                    asset_server.load(format!("{path}#{}", aseprite::CLIPS_LABEL)),
+                    clips::IDLE,
+                ),
+            ));
+        } else {
+            entity.insert(SpriteBundle {
+                transform: *transform,
+                texture: asset_server.load(&definition.sprite),
+                ..default()
+            });
+
+            if let Some((layout, sheet_clips)) = sheet {
+                entity.insert((
+                    TextureAtlas { layout, index: 0 },
+                    FrameAnimation::new(sheet_clips, clips::IDLE),
+                ));
+            }
+        }
+
+        if let Some(exit_duration) = definition.exit_duration {
+            entity.insert(ExitAnimation {
+                duration: Duration::from_secs_f32(exit_duration),
+            });
+        }
+
+        entity.insert(Weight(definition.weight));
+
+        if let Some(MovementDefinition {
+            max_speed,
+            ground,
+            airborne,
+        }) = definition.movement
+        {
+            entity.insert(
+                MovementAction::new(max_speed, ground).with_airborne(airborne.unwrap_or(ground)),
+            );
+        }
+
+        match definition.behaviour {
+            Some(BehaviourDefinition::Player) => {
+                entity.insert((PlayerBehaviour, EmitProjectile::<PlayerStomp>::default()));
+            }
+            Some(BehaviourDefinition::ChasePlayer) => {
+                entity.insert(ChasePlayerBehaviour);
+            }
+            None => {}
+        }
+
+        for action in &definition.periodic_actions {
+            match *action {
+                PeriodicActionDefinition::DasherAttack => {
+                    entity.insert(dasher_attack(
+                        Duration::from_secs_f32(tuning.dasher.attack_period),
+                        rng.stream(RngStream::Ai).gen(),
+                    ));
+                }
+            }
+        }
+
+        entity.character_with_hurtbox(definition.alignment, definition.collider.into());
+    }
+}
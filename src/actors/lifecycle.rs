@@ -0,0 +1,365 @@
+//! Spawn/despawn events of actors, despawning with exit animations and pooling.
+//!
+//! Actors are despawned by sending [`DespawnActor`]. Actors with [`ExitAnimation`] fade away first.
+//! Actors registered with
+//! [`register_pooled_actor`](super::AppRegisteringActors::register_pooled_actor)
+//! are hidden and kept for reuse instead of being despawned.
+
+use std::marker::PhantomData;
+
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{
+        component::{ComponentHooks, StorageType},
+        entity::EntityHashSet,
+        system::{EntityCommands, SystemParam},
+    },
+    prelude::*,
+    utils::Duration,
+};
+use bevy_consumable_event::{ConsumableEventApp, ConsumableEventReader};
+
+use super::Actor;
+use crate::{
+    common::{
+        animation::{fade_away::FadeAway, Animation},
+        colliders::{Alignment, CollidersCommands},
+        hits::Knockback,
+    },
+    dynamic_initialization::{DataItem, EntitySystem, ParamItem},
+};
+
+pub(super) struct LifecyclePlugin;
+
+impl Plugin for LifecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_consumable_event::<DespawnActor>()
+            .add_systems(Update, despawn_actors);
+    }
+}
+
+/// Sent after [`Actor::spawn`] or after pooled actor is reused
+#[derive(Event)]
+pub struct ActorSpawned<A: Actor> {
+    pub entity: Entity,
+    _pd: PhantomData<A>,
+}
+
+/// Sent when actor is despawned or returned to the pool
+#[derive(Event)]
+pub struct ActorDespawned<A: Actor> {
+    pub entity: Entity,
+    _pd: PhantomData<A>,
+}
+
+impl<A: Actor> ActorSpawned<A> {
+    #[inline]
+    pub(super) fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<A: Actor> ActorDespawned<A> {
+    #[inline]
+    fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            _pd: PhantomData,
+        }
+    }
+}
+
+/// Marks root entity of actor `A`, sends [`ActorDespawned`] when despawned
+pub struct ActorOf<A: Actor>(PhantomData<A>);
+
+impl<A: Actor> Default for ActorOf<A> {
+    #[inline]
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<A: Actor> Component for ActorOf<A> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            world.send_event(ActorDespawned::<A>::new(entity));
+        });
+    }
+}
+
+/// Request to despawn the actor
+#[derive(Event)]
+pub struct DespawnActor(pub Entity);
+
+/// Actor fades away for `duration` before it's despawned
+#[derive(Component)]
+pub struct ExitAnimation {
+    pub duration: Duration,
+}
+
+/// Actor is playing it's [`ExitAnimation`] or waits in the [`ActorPool`]
+#[derive(Component)]
+pub struct Despawning;
+
+/// Fades the entity away and then releases it with [`Release`]
+pub type ReleaseAnimation = Animation<FadeAway, Release>;
+
+/// Short lived attack actor, like the dasher attack or the
+/// [player stomp](super::player::stomp::PlayerStomp)
+pub struct HitboxProjectile {
+    pub position: Vec2,
+    pub alignment: Alignment,
+    pub hitbox: Collider,
+    pub knockback: Knockback,
+    /// Until the projectile has faded away and is released
+    pub lifetime: Duration,
+    /// Until the hitbox is disabled
+    pub hitbox_lifetime: Duration,
+}
+
+impl HitboxProjectile {
+    /// Parts shared by fresh and reused projectiles
+    pub fn activate(self, entity: &mut EntityCommands) {
+        entity
+            .insert((
+                Transform::from_translation(self.position.extend(0.0)),
+                Visibility::Inherited,
+                self.knockback,
+                ReleaseAnimation::new(Timer::new(self.lifetime, TimerMode::Once)),
+            ))
+            .projectile_with_hitbox_for(self.alignment, self.hitbox, self.hitbox_lifetime);
+    }
+}
+
+/// Despawns the entity, or returns it to the pool if it's [`Pooled`]
+pub struct Release;
+
+impl EntitySystem for Release {
+    type Data = (Entity, Option<&'static Pooled>);
+    type Filter = ();
+    type Param = Commands<'static, 'static>;
+
+    type In = ();
+    type Out = ();
+
+    fn run(_: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (entity, pooled) = data;
+        let mut commands = param;
+
+        release(&mut commands, entity, pooled);
+    }
+}
+
+fn release(commands: &mut Commands, entity: Entity, pooled: Option<&Pooled>) {
+    match pooled {
+        Some(pooled) => {
+            let release = pooled.release;
+            commands.add(move |world: &mut World| release(world, entity));
+        }
+        None => commands.entity(entity).despawn_recursive(),
+    }
+}
+
+/// Actor that can be reused after it's despawned, see
+/// [`register_pooled_actor`](super::AppRegisteringActors::register_pooled_actor)
+pub trait PooledActor: Actor {
+    /// Like [`Actor::spawn`], but sets up released `entity` of this actor again
+    fn respawn(self, entity: Entity, param: <Self::Param as SystemParam>::Item<'_, '_>);
+}
+
+/// Released entities of `A`, ready to be reused
+#[derive(Resource)]
+pub struct ActorPool<A: PooledActor> {
+    free: Vec<Entity>,
+    _pd: PhantomData<A>,
+}
+
+impl<A: PooledActor> Default for ActorPool<A> {
+    fn default() -> Self {
+        Self {
+            free: Vec::new(),
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl<A: PooledActor> ActorPool<A> {
+    #[inline]
+    pub(super) fn take(&mut self) -> Option<Entity> {
+        self.free.pop()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.free.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.free.is_empty()
+    }
+}
+
+/// Entity returns to the [`ActorPool`] on release
+#[derive(Component)]
+pub struct Pooled {
+    release: fn(&mut World, Entity),
+}
+
+impl Pooled {
+    #[inline]
+    pub(super) fn new<A: PooledActor>() -> Self {
+        Self {
+            release: release_to_pool::<A>,
+        }
+    }
+}
+
+fn release_to_pool<A: PooledActor>(world: &mut World, entity: Entity) {
+    let Some(mut entity_mut) = world.get_entity_mut(entity) else {
+        return;
+    };
+
+    // Already in the pool, e.g. despawn was requested before the lifetime of the actor ran out.
+    // Actors finishing their exit animation are `Despawning` too, but they still have the animation
+    if entity_mut.contains::<Despawning>() && !entity_mut.contains::<ReleaseAnimation>() {
+        return;
+    }
+
+    // Lifetime of the actor must not release it again while it's in the pool
+    entity_mut.remove::<ReleaseAnimation>().insert((
+        Visibility::Hidden,
+        CollisionLayers::NONE,
+        Despawning,
+    ));
+
+    world.resource_mut::<ActorPool<A>>().free.push(entity);
+    world.send_event(ActorDespawned::<A>::new(entity));
+}
+
+/// Pooled entities don't carry over to the next run
+pub(super) fn clear_actor_pool<A: PooledActor>(
+    mut commands: Commands,
+    mut pool: ResMut<ActorPool<A>>,
+) {
+    for entity in pool.free.drain(..) {
+        if let Some(entity) = commands.get_entity(entity) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+fn despawn_actors(
+    mut commands: Commands,
+    mut requests: ConsumableEventReader<DespawnActor>,
+    query: Query<(Option<&ExitAnimation>, Option<&Pooled>), Without<Despawning>>,
+    // Same actor may be requested several times in one frame
+    mut handled: Local<EntityHashSet>,
+) {
+    handled.clear();
+
+    for DespawnActor(entity) in requests.read_and_consume_all() {
+        let Ok((exit_animation, pooled)) = query.get(entity) else {
+            continue;
+        };
+
+        if !handled.insert(entity) {
+            continue;
+        }
+
+        match exit_animation {
+            Some(exit_animation) => {
+                commands.entity(entity).insert((
+                    Despawning,
+                    // Dying actors don't interact with anything
+                    CollisionLayers::NONE,
+                    ReleaseAnimation::new(Timer::new(exit_animation.duration, TimerMode::Once)),
+                ));
+            }
+            None => release(&mut commands, entity, pooled),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::dynamic_initialization::DynamicInitializationPlugin;
+
+    struct TestActor;
+
+    impl Actor for TestActor {
+        type Param = ();
+
+        fn spawn(self, _: ()) -> Entity {
+            Entity::PLACEHOLDER
+        }
+    }
+
+    impl PooledActor for TestActor {
+        fn respawn(self, _: Entity, _: ()) {}
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, DynamicInitializationPlugin))
+            .init_resource::<ActorPool<TestActor>>()
+            .add_event::<ActorDespawned<TestActor>>();
+        app
+    }
+
+    #[test]
+    fn released_twice_enters_pool_once() {
+        let mut app = app();
+        let entity = app
+            .world_mut()
+            .spawn((
+                Pooled::new::<TestActor>(),
+                ReleaseAnimation::new(Timer::from_seconds(1., TimerMode::Once)),
+            ))
+            .id();
+
+        release_to_pool::<TestActor>(app.world_mut(), entity);
+        release_to_pool::<TestActor>(app.world_mut(), entity);
+
+        assert_eq!(app.world().resource::<ActorPool<TestActor>>().len(), 1);
+        assert!(!app.world().entity(entity).contains::<ReleaseAnimation>());
+    }
+
+    #[test]
+    fn exit_animation_releases_to_pool() {
+        let mut app = app();
+        let entity = app
+            .world_mut()
+            .spawn((
+                Pooled::new::<TestActor>(),
+                Despawning,
+                ReleaseAnimation::new(Timer::from_seconds(1., TimerMode::Once)),
+            ))
+            .id();
+
+        release_to_pool::<TestActor>(app.world_mut(), entity);
+
+        assert_eq!(app.world().resource::<ActorPool<TestActor>>().len(), 1);
+    }
+
+    #[test]
+    fn cleared_pool_despawns_free_entities() {
+        let mut app = app();
+        let entity = app.world_mut().spawn(Pooled::new::<TestActor>()).id();
+        release_to_pool::<TestActor>(app.world_mut(), entity);
+
+        app.world_mut()
+            .run_system_once(clear_actor_pool::<TestActor>);
+
+        assert!(app.world().resource::<ActorPool<TestActor>>().is_empty());
+        assert!(app.world().get_entity(entity).is_none());
+    }
+}
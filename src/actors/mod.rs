@@ -1,22 +1,40 @@
 use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_consumable_event::*;
 
-use crate::action_behaviour::{actions::emit_projectile::EmitProjectile, ActionBehaviourApp};
+use crate::{
+    action_behaviour::{actions::emit_projectile::EmitProjectile, ActionBehaviourApp},
+    introspection::RegisteredTypes,
+    GameState,
+};
+use lifecycle::{
+    clear_actor_pool, ActorDespawned, ActorOf, ActorPool, ActorSpawned, Despawning, Pooled,
+    PooledActor,
+};
+use spawn_telegraph::PlacedActor;
 
 pub mod dasher;
+pub mod definition;
+pub mod lifecycle;
 pub mod player;
+pub mod spawn_telegraph;
 
 pub struct RegisterActors;
 impl Plugin for RegisterActors {
     fn build(&self, app: &mut App) {
-        app.add_plugins((player::RegisterPlayer, dasher::RegisterDasher));
+        app.add_plugins((
+            lifecycle::LifecyclePlugin,
+            player::RegisterPlayer,
+            dasher::RegisterDasher,
+            definition::RegisterDataActor,
+        ));
     }
 }
 
 pub trait Actor: Send + Sync + 'static {
     type Param: SystemParam;
 
-    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>);
+    /// Returns the root entity of the actor
+    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) -> Entity;
 }
 
 #[derive(Event, Default, Deref, DerefMut)]
@@ -24,23 +42,87 @@ pub struct SpawnActor<A: Actor>(pub A);
 
 pub trait AppRegisteringActors {
     fn register_actor<T: Actor>(&mut self) -> &mut Self;
+
+    /// Like [`register_actor`](AppRegisteringActors::register_actor),
+    /// but released entities are reused by the next spawns
+    fn register_pooled_actor<T: PooledActor>(&mut self) -> &mut Self;
+
+    /// Enables [`TelegraphSpawn`](spawn_telegraph::TelegraphSpawn) for already registered actor
+    fn register_telegraphed_spawn<T: PlacedActor>(&mut self) -> &mut Self;
 }
 
 impl AppRegisteringActors for App {
     fn register_actor<A: Actor>(&mut self) -> &mut Self {
-        // Idk better way to do it, but this is exceptional case and this architecture is pretty neat
-        self.register_action::<EmitProjectile<A>>();
+        register_actor_events::<A>(self).add_systems(Update, spawn_actor_system::<A>)
+    }
+
+    fn register_pooled_actor<A: PooledActor>(&mut self) -> &mut Self {
+        register_actor_events::<A>(self)
+            .init_resource::<ActorPool<A>>()
+            .add_systems(Update, spawn_pooled_actor_system::<A>)
+            .add_systems(OnExit(GameState::Playing), clear_actor_pool::<A>)
+    }
 
-        self.add_persistent_consumable_event::<SpawnActor<A>>()
-            .add_systems(Update, spawn_actor_system::<A>)
+    fn register_telegraphed_spawn<A: PlacedActor>(&mut self) -> &mut Self {
+        spawn_telegraph::register_telegraphed_spawn::<A>(self);
+        self
     }
 }
 
+fn register_actor_events<A: Actor>(app: &mut App) -> &mut App {
+    RegisteredTypes::get(app)
+        .actors
+        .push(std::any::type_name::<A>());
+
+    // Idk better way to do it, but this is exceptional case and this architecture is pretty neat
+    app.register_action::<EmitProjectile<A>>();
+
+    app.add_persistent_consumable_event::<SpawnActor<A>>()
+        .add_event::<ActorSpawned<A>>()
+        .add_event::<ActorDespawned<A>>()
+}
+
 fn spawn_actor_system<A: Actor>(
+    mut commands: Commands,
     mut events: ConsumableEventReader<SpawnActor<A>>,
+    mut spawned: EventWriter<ActorSpawned<A>>,
     mut param: ParamSet<(A::Param,)>,
 ) {
     for event in events.read_and_consume_all() {
-        event.0.spawn(param.p0())
+        let entity = event.0.spawn(param.p0());
+
+        commands.entity(entity).insert(ActorOf::<A>::default());
+        spawned.send(ActorSpawned::new(entity));
+    }
+}
+
+fn spawn_pooled_actor_system<A: PooledActor>(
+    mut commands: Commands,
+    mut events: ConsumableEventReader<SpawnActor<A>>,
+    mut pool: ResMut<ActorPool<A>>,
+    mut spawned: EventWriter<ActorSpawned<A>>,
+    mut param: ParamSet<(A::Param,)>,
+) {
+    for event in events.read_and_consume_all() {
+        // Pooled entity might have been despawned by something else
+        let reused = std::iter::from_fn(|| pool.take())
+            .find(|&entity| commands.get_entity(entity).is_some());
+
+        let entity = match reused {
+            Some(entity) => {
+                commands.entity(entity).remove::<Despawning>();
+                event.0.respawn(entity, param.p0());
+                entity
+            }
+            None => {
+                let entity = event.0.spawn(param.p0());
+                commands
+                    .entity(entity)
+                    .insert((ActorOf::<A>::default(), Pooled::new::<A>()));
+                entity
+            }
+        };
+
+        spawned.send(ActorSpawned::new(entity));
     }
 }
@@ -1,16 +1,77 @@
 use super::{Actor, AppRegisteringActors};
 use crate::{
-    action_behaviour::{actions::movement::MovementAction, behaviours::player::PlayerBehaviour},
-    common::colliders::{Alignment, CollidersCommands},
+    action_behaviour::{
+        actions::{emit_projectile::EmitProjectile, movement::MovementAction},
+        behaviours::player::PlayerBehaviour,
+    },
+    camera::CameraTarget,
+    common::{
+        colliders::{Alignment, CollidersCommands},
+        frame_animation::{clips, FrameAnimation, FrameClip, FrameClips},
+        hits::Weight,
+    },
+    tuning::Tuning,
 };
 use avian2d::collision::Collider;
-use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::Duration};
+use stomp::PlayerStomp;
+
+pub mod stomp;
 
 pub struct RegisterPlayer;
 
 impl Plugin for RegisterPlayer {
     fn build(&self, app: &mut App) {
-        app.register_actor::<Player>();
+        app.init_resource::<PlayerSpriteSheet>()
+            .register_actor::<Player>()
+            .register_pooled_actor::<PlayerStomp>()
+            .add_systems(
+                Update,
+                apply_player_tuning.run_if(resource_changed::<Tuning>),
+            );
+    }
+}
+
+const PLAYER_FRAME_SIZE: UVec2 = UVec2::new(10, 10);
+const PLAYER_FRAME_DURATION: Duration = Duration::from_millis(100);
+
+// `textures/player.png` has only one frame for now, but clips are already in place
+#[derive(Resource)]
+pub struct PlayerSpriteSheet {
+    layout: Handle<TextureAtlasLayout>,
+    clips: Handle<FrameClips>,
+}
+
+impl FromWorld for PlayerSpriteSheet {
+    fn from_world(world: &mut World) -> Self {
+        let layout =
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(TextureAtlasLayout::from_grid(
+                    PLAYER_FRAME_SIZE,
+                    1,
+                    1,
+                    None,
+                    None,
+                ));
+
+        let clips = world.resource_mut::<Assets<FrameClips>>().add(
+            FrameClips::default()
+                .with_clip(
+                    clips::IDLE,
+                    FrameClip::from_indices([0], PLAYER_FRAME_DURATION, true),
+                )
+                .with_clip(
+                    clips::WALK,
+                    FrameClip::from_indices([0], PLAYER_FRAME_DURATION, true),
+                )
+                .with_clip(
+                    clips::FALL,
+                    FrameClip::from_indices([0], PLAYER_FRAME_DURATION, false),
+                ),
+        );
+
+        Self { layout, clips }
     }
 }
 
@@ -19,12 +80,17 @@ pub struct Player {
 }
 
 impl Actor for Player {
-    type Param = (Commands<'static, 'static>, Res<'static, AssetServer>);
+    type Param = (
+        Commands<'static, 'static>,
+        Res<'static, AssetServer>,
+        Res<'static, PlayerSpriteSheet>,
+        Res<'static, Tuning>,
+    );
 
-    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) {
+    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) -> Entity {
         info!("Spawn player");
 
-        let (mut commands, asset_server) = param;
+        let (mut commands, asset_server, sprite_sheet, tuning) = param;
 
         commands
             .spawn((
@@ -33,9 +99,31 @@ impl Actor for Player {
                     texture: asset_server.load("textures/player.png"),
                     ..default()
                 },
+                TextureAtlas {
+                    layout: sprite_sheet.layout.clone(),
+                    index: 0,
+                },
+                FrameAnimation::new(sprite_sheet.clips.clone(), clips::IDLE),
                 PlayerBehaviour,
-                MovementAction::new(100.0, 0.7),
+                EmitProjectile::<PlayerStomp>::default(),
+                CameraTarget,
+                MovementAction::new(tuning.player.max_speed, tuning.player.ground)
+                    .with_airborne(tuning.player.airborne),
+                Weight(tuning.player.weight),
             ))
-            .character_with_hurtbox(Alignment::Player, Collider::circle(4.0));
+            .character_with_hurtbox(Alignment::Player, Collider::circle(4.0))
+            .id()
+    }
+}
+
+fn apply_player_tuning(
+    tuning: Res<Tuning>,
+    mut query: Query<(&mut MovementAction, &mut Weight), With<PlayerBehaviour>>,
+) {
+    for (mut movement, mut weight) in query.iter_mut() {
+        movement.max_speed = tuning.player.max_speed;
+        movement.ground = tuning.player.ground;
+        movement.airborne_params = tuning.player.airborne;
+        weight.0 = tuning.player.weight;
     }
 }
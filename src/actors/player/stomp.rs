@@ -0,0 +1,81 @@
+//! Destroying a tile knocks enemies around the player away, into the holes nearby.
+
+use avian2d::prelude::*;
+use bevy::{
+    ecs::system::{EntityCommands, SystemParam},
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::Duration,
+};
+
+use super::super::{
+    lifecycle::{HitboxProjectile, PooledActor},
+    Actor,
+};
+use crate::{
+    common::{colliders::Alignment, hits::Knockback},
+    tuning::Tuning,
+};
+
+const STOMP_COLOR: Color = Color::linear_rgba(1., 1., 1., 0.5);
+
+/// Emitted by the player with [`EmitProjectile`](crate::action_behaviour::actions::emit_projectile::EmitProjectile)
+pub struct PlayerStomp {
+    pub position: Vec2,
+}
+
+impl Actor for PlayerStomp {
+    type Param = (
+        Commands<'static, 'static>,
+        ResMut<'static, Assets<Mesh>>,
+        ResMut<'static, Assets<ColorMaterial>>,
+        Res<'static, Tuning>,
+        Query<'static, 'static, (&'static Mesh2dHandle, &'static Handle<ColorMaterial>)>,
+    );
+
+    fn spawn(self, param: <Self::Param as SystemParam>::Item<'_, '_>) -> Entity {
+        let (mut commands, mut meshes, mut materials, tuning, _) = param;
+
+        let mut entity = commands.spawn(MaterialMesh2dBundle {
+            mesh: meshes.add(Circle::new(tuning.player.stomp_radius)).into(),
+            material: materials.add(ColorMaterial::from_color(STOMP_COLOR)),
+            ..default()
+        });
+        self.activate(&mut entity, &tuning);
+
+        entity.id()
+    }
+}
+
+impl PooledActor for PlayerStomp {
+    fn respawn(self, entity: Entity, param: <Self::Param as SystemParam>::Item<'_, '_>) {
+        let (mut commands, mut meshes, mut materials, tuning, stomps) = param;
+
+        if let Ok((mesh, material)) = stomps.get(entity) {
+            meshes.insert(&mesh.0, Circle::new(tuning.player.stomp_radius).into());
+            if let Some(material) = materials.get_mut(material) {
+                material.color = STOMP_COLOR;
+            }
+        }
+
+        self.activate(&mut commands.entity(entity), &tuning);
+    }
+}
+
+impl PlayerStomp {
+    /// Parts shared by fresh and reused stomps
+    fn activate(self, entity: &mut EntityCommands, tuning: &Tuning) {
+        HitboxProjectile {
+            position: self.position,
+            alignment: Alignment::Player,
+            hitbox: Collider::circle(tuning.player.stomp_radius),
+            knockback: Knockback {
+                strength: tuning.player.stomp_knockback_strength,
+                duration: Duration::from_secs_f32(tuning.player.stomp_knockback_duration),
+            },
+            lifetime: Duration::from_secs_f32(tuning.player.stomp_lifetime),
+            hitbox_lifetime: Duration::from_secs_f32(tuning.player.stomp_hitbox_lifetime),
+        }
+        .activate(entity);
+    }
+}
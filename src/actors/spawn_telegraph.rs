@@ -0,0 +1,207 @@
+//! Spawn stage that checks the position of the actor before spawning it.
+//!
+//! Requested positions are scattered randomly with the [`RngStream::Spawning`] stream.
+//! Actors requested with [`TelegraphSpawn`] must stand on an alive tile and keep distance from the
+//! player. Invalid positions are moved to the closest valid tile, if there is none the spawn is
+//! delayed until there is. A marker shows up at the position and the actor is spawned when it's
+//! fully visible.
+
+use std::{f32::consts::TAU, marker::PhantomData};
+
+use bevy::{prelude::*, utils::Duration};
+use bevy_consumable_event::{
+    ConsumableEventApp, ConsumableEventReader, ConsumableEventWriter, ConsumableEvents,
+};
+use rand::Rng;
+
+use super::{lifecycle::ActorOf, player::Player, spawn_actor_system, Actor, SpawnActor};
+use crate::{
+    common::{
+        animation::{show_up::ShowUp, Animation},
+        rng::{GameRng, RngStream},
+    },
+    dynamic_initialization::{DataItem, EntitySystem, ParamItem},
+    playing_state::LandTiles,
+    tuning::Tuning,
+    GameState,
+};
+
+const TELEGRAPH_COLOR: Color = Color::linear_rgb(1., 0.2, 0.2);
+const TELEGRAPH_SIZE: Vec2 = Vec2::splat(6.);
+
+/// Actor with position that can be changed before it's spawned
+pub trait PlacedActor: Actor {
+    fn position(&self) -> Vec2;
+
+    fn set_position(&mut self, position: Vec2);
+}
+
+/// Spawns actor after the telegraph at it's validated position
+#[derive(Event)]
+pub struct TelegraphSpawn<A: PlacedActor>(pub A);
+
+pub(super) fn register_telegraphed_spawn<A: PlacedActor>(app: &mut App) {
+    app.add_persistent_consumable_event::<TelegraphSpawn<A>>()
+        .init_resource::<DelayedSpawns<A>>()
+        .add_systems(
+            Update,
+            // Player spawned on the same frame is kept at distance
+            place_telegraphs::<A>
+                .after(spawn_actor_system::<Player>)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::Playing), clear_telegraphs::<A>);
+}
+
+/// Spawns with no valid position at the moment
+#[derive(Resource)]
+struct DelayedSpawns<A: PlacedActor>(Vec<A>);
+
+impl<A: PlacedActor> Default for DelayedSpawns<A> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+/// Marker showing where `A` will be spawned
+#[derive(Component)]
+pub struct Telegraph<A: PlacedActor> {
+    actor: Option<A>,
+}
+
+pub type TelegraphAnimation<A> = Animation<ShowUp, SpawnTelegraphed<A>>;
+
+fn place_telegraphs<A: PlacedActor>(
+    mut commands: Commands,
+    mut requests: ConsumableEventReader<TelegraphSpawn<A>>,
+    mut delayed: ResMut<DelayedSpawns<A>>,
+    tiles: Res<LandTiles>,
+    tuning: Res<Tuning>,
+    mut rng: ResMut<GameRng>,
+    players: Query<&Transform, With<ActorOf<Player>>>,
+) {
+    let rng = rng.stream(RngStream::Spawning);
+    let scatter = tuning.spawn.scatter;
+
+    // Delayed spawns were already scattered
+    let requested: Vec<A> = requests
+        .read_and_consume_all()
+        .map(|TelegraphSpawn(mut actor)| {
+            // Uniform in the circle
+            let offset =
+                Vec2::from_angle(rng.gen_range(0.0..TAU)) * scatter * rng.gen::<f32>().sqrt();
+            actor.set_position(actor.position() + offset);
+            actor
+        })
+        .collect();
+    let to_place: Vec<A> = delayed.0.drain(..).chain(requested).collect();
+
+    let is_valid = |position: Vec2| is_valid_position(position, &tiles, &tuning, &players);
+
+    for mut actor in to_place {
+        let requested = actor.position();
+
+        let position = if is_valid(requested) {
+            Some(requested)
+        } else {
+            tiles
+                .alive_tile_centers()
+                .filter(|&center| is_valid(center))
+                .min_by(|a, b| {
+                    a.distance_squared(requested)
+                        .total_cmp(&b.distance_squared(requested))
+                })
+        };
+
+        let Some(position) = position else {
+            delayed.0.push(actor);
+            continue;
+        };
+
+        actor.set_position(position);
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: TELEGRAPH_COLOR.with_alpha(0.),
+                    custom_size: Some(TELEGRAPH_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.0)),
+                ..default()
+            },
+            Telegraph { actor: Some(actor) },
+            TelegraphAnimation::<A>::new(Timer::new(
+                Duration::from_secs_f32(tuning.spawn.telegraph_duration),
+                TimerMode::Once,
+            )),
+        ));
+    }
+}
+
+fn is_valid_position(
+    position: Vec2,
+    tiles: &LandTiles,
+    tuning: &Tuning,
+    players: &Query<&Transform, With<ActorOf<Player>>>,
+) -> bool {
+    let min_distance = tuning.spawn.min_player_distance;
+
+    tiles.on_ground(position)
+        && players
+            .iter()
+            .all(|player| player.translation.xy().distance(position) >= min_distance)
+}
+
+/// Spawns requested in one run don't carry over to the next one
+fn clear_telegraphs<A: PlacedActor>(
+    mut commands: Commands,
+    mut requests: ResMut<ConsumableEvents<TelegraphSpawn<A>>>,
+    mut delayed: ResMut<DelayedSpawns<A>>,
+    telegraphs: Query<Entity, With<Telegraph<A>>>,
+) {
+    requests.clear();
+    delayed.0.clear();
+
+    for telegraph in telegraphs.iter() {
+        commands.entity(telegraph).despawn_recursive();
+    }
+}
+
+/// Spawns the actor of the [`Telegraph`] and removes the marker.
+/// If position became invalid while telegraphing (the tile fell or the player came too close),
+/// actor goes through [`TelegraphSpawn`] again.
+pub struct SpawnTelegraphed<A: PlacedActor>(PhantomData<A>);
+
+impl<A: PlacedActor> EntitySystem for SpawnTelegraphed<A> {
+    type Data = (Entity, &'static mut Telegraph<A>);
+    type Filter = ();
+    type Param = (
+        Commands<'static, 'static>,
+        Res<'static, LandTiles>,
+        Res<'static, Tuning>,
+        Query<'static, 'static, &'static Transform, With<ActorOf<Player>>>,
+        ConsumableEventWriter<'static, SpawnActor<A>>,
+        ConsumableEventWriter<'static, TelegraphSpawn<A>>,
+    );
+
+    type In = ();
+    type Out = ();
+
+    fn run(_: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (entity, mut telegraph) = data;
+        let (mut commands, tiles, tuning, players, mut spawn, mut retry) = param;
+
+        commands.entity(entity).despawn_recursive();
+
+        let Some(actor) = telegraph.actor.take() else {
+            return;
+        };
+
+        if is_valid_position(actor.position(), &tiles, &tuning, &players) {
+            spawn.send(SpawnActor(actor));
+        } else {
+            retry.send(TelegraphSpawn(actor));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! Procedural arenas for the endless mode.
+//!
+//! Ground is scattered randomly and smoothed into islands. Only the island with the player spawn is
+//! kept, so everything is reachable from it. Cells of the island are then turned into
+//! [`CellKind`]s by their weights, and ground cut off by the new holes is dropped. The result is a regular [`LevelConfig`], the same
+//! seed and params always produce the same arena.
+
+use bevy::prelude::*;
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::StdRng,
+    seq::SliceRandom,
+    Rng, SeedableRng,
+};
+
+use crate::{
+    common::rng::{GameRng, RngStream},
+    playing_state::{ArenaEdges, LandTiles, LayoutSetup, LevelConfig},
+    tuning::Tuning,
+    GameState,
+};
+
+pub struct ArenaGeneratorPlugin;
+
+impl Plugin for ArenaGeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EndlessMode>().add_systems(
+            OnEnter(GameState::Playing),
+            generate_endless_arena.before(LayoutSetup),
+        );
+    }
+}
+
+/// Arena is generated again if it's smaller
+const MAX_ATTEMPTS: u32 = 16;
+const PILLAR_SIZE: Vec2 = Vec2::splat(10.0);
+
+/// What a cell of the island becomes
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CellKind {
+    Ground,
+    /// Ground with a pillar in the centre
+    Pillar,
+    /// Void inside the island
+    Hole,
+}
+
+#[derive(Clone, Debug)]
+pub struct ArenaParams {
+    pub min_size: UVec2,
+    pub max_size: UVec2,
+    /// Chance of a cell to be ground before smoothing
+    pub ground_chance: f64,
+    pub smoothing_steps: u32,
+    /// Part of the cells that must be reachable ground
+    pub min_ground: f32,
+    /// Relative weights of the kinds of island cells.
+    /// Cells around the player spawn are always ground.
+    pub cell_kinds: Vec<(CellKind, f32)>,
+    pub enemy_count: usize,
+    /// Enemies don't spawn closer to the player, in tiles
+    pub min_enemy_distance: i32,
+    pub edges: ArenaEdges,
+}
+
+impl Default for ArenaParams {
+    fn default() -> Self {
+        Self {
+            min_size: UVec2::new(5, 5),
+            max_size: UVec2::new(8, 8),
+            ground_chance: 0.65,
+            smoothing_steps: 2,
+            min_ground: 0.45,
+            cell_kinds: vec![
+                (CellKind::Ground, 0.86),
+                (CellKind::Pillar, 0.08),
+                (CellKind::Hole, 0.06),
+            ],
+            enemy_count: 2,
+            min_enemy_distance: 2,
+            edges: ArenaEdges::Walls,
+        }
+    }
+}
+
+/// Generates arena with connected ground from the player spawn
+pub fn generate_arena(seed: u64, params: &ArenaParams, tile_size: f32) -> LevelConfig {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for _ in 0..MAX_ATTEMPTS {
+        if let Some(level) = try_generate(&mut rng, params, tile_size) {
+            return level;
+        }
+    }
+
+    warn!("Could not generate arena with seed {seed}, using a flat one");
+    let size = params.min_size;
+    LevelConfig {
+        size,
+        voids: Vec::new(),
+        edges: params.edges,
+        pillars: Vec::new(),
+        player_spawn: LandTiles::new(size, tile_size).array_to_world(size.as_ivec2() / 2),
+        enemy_spawns: Vec::new(),
+    }
+}
+
+fn try_generate(rng: &mut StdRng, params: &ArenaParams, tile_size: f32) -> Option<LevelConfig> {
+    let size = UVec2::new(
+        rng.gen_range(params.min_size.x..=params.max_size.x),
+        rng.gen_range(params.min_size.y..=params.max_size.y),
+    );
+    let spawn = size.as_ivec2() / 2;
+
+    let mut tiles = LandTiles::new(size, tile_size);
+    let cells: Vec<IVec2> = tiles.cells().map(|(cell, _)| cell).collect();
+
+    // Player always has some ground around
+    let keep_ground = |cell: IVec2| (cell - spawn).abs().element_sum() <= 1;
+
+    let mut ground: Vec<bool> = cells
+        .iter()
+        .map(|&cell| keep_ground(cell) || rng.gen_bool(params.ground_chance))
+        .collect();
+
+    for _ in 0..params.smoothing_steps {
+        let voids = voids(&cells, &ground);
+        tiles.reset(size, &voids);
+
+        ground = cells
+            .iter()
+            .map(|&cell| {
+                let around = tiles
+                    .neighbours_8(cell)
+                    .filter(|&neighbour| tiles.is_alive(neighbour))
+                    .count()
+                    + tiles.is_alive(cell) as usize;
+
+                keep_ground(cell) || around >= 5
+            })
+            .collect();
+    }
+
+    tiles.reset(size, &voids(&cells, &ground));
+
+    // All weights zero or negative leave plain ground
+    let kinds = WeightedIndex::new(params.cell_kinds.iter().map(|&(_, weight)| weight)).ok();
+    let mut pillar_cells = Vec::new();
+    let mut holes = Vec::new();
+
+    for cell in tiles.island(spawn) {
+        let kind = match &kinds {
+            Some(kinds) if !keep_ground(cell) => params.cell_kinds[kinds.sample(rng)].0,
+            _ => CellKind::Ground,
+        };
+
+        match kind {
+            CellKind::Ground => {}
+            CellKind::Pillar => pillar_cells.push(cell),
+            CellKind::Hole => holes.push(cell),
+        }
+    }
+
+    let mut voids = voids(&cells, &ground);
+    voids.extend(holes);
+    tiles.reset(size, &voids);
+
+    let island = tiles.island(spawn);
+    if (island.len() as f32) < params.min_ground * cells.len() as f32 {
+        return None;
+    }
+
+    // Ground that can't be reached becomes void
+    let voids: Vec<IVec2> = cells
+        .iter()
+        .copied()
+        .filter(|cell| !island.contains(cell))
+        .collect();
+
+    let mut enemy_cells: Vec<IVec2> = island
+        .iter()
+        .copied()
+        .filter(|&cell| (cell - spawn).abs().max_element() >= params.min_enemy_distance)
+        .filter(|cell| !pillar_cells.contains(cell))
+        .collect();
+    enemy_cells.shuffle(rng);
+    enemy_cells.truncate(params.enemy_count);
+
+    let pillars = pillar_cells
+        .into_iter()
+        .filter(|cell| island.contains(cell))
+        .map(|cell| Rect::from_center_size(tiles.array_to_world(cell), PILLAR_SIZE))
+        .collect();
+
+    Some(LevelConfig {
+        size,
+        voids,
+        edges: params.edges,
+        pillars,
+        player_spawn: tiles.array_to_world(spawn),
+        enemy_spawns: enemy_cells
+            .into_iter()
+            .map(|cell| tiles.array_to_world(cell))
+            .collect(),
+    })
+}
+
+fn voids(cells: &[IVec2], ground: &[bool]) -> Vec<IVec2> {
+    cells
+        .iter()
+        .zip(ground)
+        .filter(|(_, &ground)| !ground)
+        .map(|(&cell, _)| cell)
+        .collect()
+}
+
+/// Runs in the endless mode are played on an arena generated from the [`RngStream::Procgen`]
+#[derive(Resource, Default)]
+pub struct EndlessMode {
+    pub enabled: bool,
+    pub params: ArenaParams,
+    /// Level replaced by generated ones, restored when the mode is disabled
+    hand_made: Option<LevelConfig>,
+}
+
+fn generate_endless_arena(
+    mut endless: ResMut<EndlessMode>,
+    mut level: ResMut<LevelConfig>,
+    mut rng: ResMut<GameRng>,
+    tuning: Res<Tuning>,
+) {
+    if !endless.enabled {
+        if let Some(hand_made) = endless.hand_made.take() {
+            *level = hand_made;
+        }
+        return;
+    }
+
+    let seed = rng.stream(RngStream::Procgen).gen();
+    let generated = generate_arena(seed, &endless.params, tuning.tiles.tile_size);
+    let previous = std::mem::replace(&mut *level, generated);
+    endless.hand_made.get_or_insert(previous);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILE_SIZE: f32 = 40.;
+
+    #[test]
+    fn same_seed_same_arena() {
+        let params = ArenaParams::default();
+
+        for seed in 0..8 {
+            let a = generate_arena(seed, &params, TILE_SIZE);
+            let b = generate_arena(seed, &params, TILE_SIZE);
+
+            assert_eq!(a.size, b.size);
+            assert_eq!(a.voids, b.voids);
+            assert_eq!(a.pillars, b.pillars);
+            assert_eq!(a.enemy_spawns, b.enemy_spawns);
+        }
+    }
+
+    #[test]
+    fn ground_is_reachable_from_player() {
+        let params = ArenaParams::default();
+
+        for seed in 0..32 {
+            let level = generate_arena(seed, &params, TILE_SIZE);
+
+            let mut tiles = LandTiles::new(level.size, TILE_SIZE);
+            tiles.reset(level.size, &level.voids);
+
+            let player = tiles.world_to_array(level.player_spawn).unwrap();
+            let ground = tiles
+                .cells()
+                .filter(|&(cell, _)| tiles.is_alive(cell))
+                .count();
+            assert_eq!(tiles.island(player).len(), ground);
+
+            for &enemy in &level.enemy_spawns {
+                assert!(tiles.same_island(level.player_spawn, enemy));
+            }
+        }
+    }
+
+    #[test]
+    fn cell_kinds_follow_weights() {
+        let ground_only = ArenaParams {
+            cell_kinds: vec![(CellKind::Ground, 1.), (CellKind::Pillar, 0.)],
+            ..default()
+        };
+        let pillars_only = ArenaParams {
+            cell_kinds: vec![(CellKind::Pillar, 1.)],
+            ..default()
+        };
+
+        for seed in 0..8 {
+            assert!(generate_arena(seed, &ground_only, TILE_SIZE)
+                .pillars
+                .is_empty());
+
+            let level = generate_arena(seed, &pillars_only, TILE_SIZE);
+            let mut tiles = LandTiles::new(level.size, TILE_SIZE);
+            tiles.reset(level.size, &level.voids);
+
+            // Everything but the cells around the player spawn
+            let ground = tiles
+                .cells()
+                .filter(|&(cell, _)| tiles.is_alive(cell))
+                .count();
+            assert_eq!(level.pillars.len(), ground - 5);
+        }
+    }
+}
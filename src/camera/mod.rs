@@ -0,0 +1,323 @@
+use bevy::{prelude::*, render::camera::ScalingMode, utils::Duration, window::PrimaryWindow};
+use bevy_consumable_event::{ConsumableEventApp, ConsumableEventReader};
+use pixel_perfect::PixelPerfectCanvas;
+use rand::Rng;
+
+use crate::{
+    common::rng::{GameRng, RngStream},
+    playing_state::LandTiles,
+};
+
+pub mod pixel_perfect;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraSettings>()
+            .init_resource::<CursorWorldPosition>()
+            .add_consumable_event::<AddTrauma>()
+            .add_consumable_event::<ZoomPulse>()
+            .add_systems(
+                Startup,
+                (setup_camera, pixel_perfect::setup_pixel_perfect).chain(),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    pixel_perfect::fit_canvas.run_if(resource_exists::<PixelPerfectCanvas>),
+                    update_cursor_world_position,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    read_camera_events,
+                    frame_arena,
+                    follow_target,
+                    apply_camera_rig,
+                )
+                    .chain()
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct CameraSettings {
+    /// World units visible around the arena
+    pub arena_margin: f32,
+    /// Follow [`CameraTarget`] instead of showing the whole arena
+    pub follow: Option<CameraFollow>,
+    /// Offset in world units at full trauma
+    pub max_shake_offset: f32,
+    /// Rotation in radians at full trauma
+    pub max_shake_angle: f32,
+    /// Trauma lost per second
+    pub trauma_decay: f32,
+    /// Virtual resolution of the [pixel perfect](pixel_perfect) rendering.
+    /// Read once on startup, `None` renders directly to the window.
+    pub pixel_perfect: Option<UVec2>,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            arena_margin: 10.,
+            follow: None,
+            max_shake_offset: 6.,
+            max_shake_angle: 0.05,
+            trauma_decay: 1.5,
+            // Arena is framed with a whole number of pixels per world unit or the other way around,
+            // see `frame_arena`
+            pixel_perfect: Some(UVec2::new(320, 180)),
+        }
+    }
+}
+
+pub struct CameraFollow {
+    /// Size of the visible area in world units
+    pub view_size: Vec2,
+    /// Half extents of the area around the camera centre, in which target can move freely
+    pub deadzone: Vec2,
+    /// How fast camera catches up with the target, higher is faster
+    pub smoothing: f32,
+}
+
+/// Camera rendering the game, there is only one
+#[derive(Component)]
+pub struct MainCamera;
+
+/// Entity followed by the camera when [`CameraSettings::follow`] is set
+#[derive(Component)]
+pub struct CameraTarget;
+
+/// Camera state before shake and zoom pulses are applied
+#[derive(Component, Default)]
+pub struct CameraRig {
+    pub focus: Vec2,
+    /// In `0.0..=1.0`, shake is proportional to the square of it
+    pub trauma: f32,
+    /// Offset of the shake noise, picked randomly when the camera starts shaking
+    shake_phase: f32,
+    zoom_pulse: Option<(f32, Timer)>,
+}
+
+/// Position of the mouse cursor or the first touch in world coordinates
+#[derive(Resource, Default)]
+pub struct CursorWorldPosition(pub Option<Vec2>);
+
+/// Adds trauma to the camera, making it shake. Trauma is clamped to `1.0`.
+#[derive(Event)]
+pub struct AddTrauma(pub f32);
+
+/// Zooms camera in by `amount` (`0.1` is 10%) and back during `duration`
+#[derive(Event)]
+pub struct ZoomPulse {
+    pub amount: f32,
+    pub duration: Duration,
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn((
+        Camera2dBundle {
+            projection: OrthographicProjection {
+                far: 1000.,
+                near: -1000.,
+                scaling_mode: ScalingMode::WindowSize(4.),
+                ..default()
+            },
+            ..default()
+        },
+        MainCamera,
+        CameraRig::default(),
+    ));
+}
+
+fn update_cursor_world_position(
+    mut cursor: ResMut<CursorWorldPosition>,
+    touches: Res<Touches>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    canvas: Option<Res<PixelPerfectCanvas>>,
+    camera: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    let Ok((camera, camera_transform)) = camera.get_single() else {
+        return;
+    };
+
+    let window_position = touches.first_pressed_position().or_else(|| {
+        windows
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+    });
+
+    let viewport_position = match &canvas {
+        Some(canvas) => window_position.and_then(|position| canvas.window_to_viewport(position)),
+        None => window_position,
+    };
+
+    let world_position = viewport_position
+        .and_then(|position| camera.viewport_to_world_2d(camera_transform, position));
+
+    if cursor.0 != world_position {
+        cursor.0 = world_position;
+    }
+}
+
+fn read_camera_events(
+    mut trauma: ConsumableEventReader<AddTrauma>,
+    mut zoom: ConsumableEventReader<ZoomPulse>,
+    mut rng: ResMut<GameRng>,
+    mut query: Query<&mut CameraRig, With<MainCamera>>,
+) {
+    let Ok(mut rig) = query.get_single_mut() else {
+        return;
+    };
+
+    for AddTrauma(amount) in trauma.read_and_consume_all() {
+        // Every shake looks different
+        if rig.trauma == 0. {
+            rig.shake_phase = rng.stream(RngStream::Cosmetic).gen_range(0.0..100.);
+        }
+        rig.trauma = (rig.trauma + amount).clamp(0., 1.);
+    }
+
+    for ZoomPulse { amount, duration } in zoom.read_and_consume_all() {
+        rig.zoom_pulse = Some((amount, Timer::new(duration, TimerMode::Once)));
+    }
+}
+
+fn frame_arena(
+    settings: Res<CameraSettings>,
+    tiles: Res<LandTiles>,
+    canvas: Option<Res<PixelPerfectCanvas>>,
+    mut query: Query<&mut OrthographicProjection, With<MainCamera>>,
+) {
+    let Ok(mut projection) = query.get_single_mut() else {
+        return;
+    };
+
+    let view_size = match &settings.follow {
+        Some(follow) => follow.view_size,
+        None => tiles.world_rect().size() + Vec2::splat(settings.arena_margin * 2.),
+    };
+
+    let scaling_mode = match &canvas {
+        // Sprite texels must cover the same number of canvas pixels, otherwise they have uneven
+        // sizes and shimmer while moving
+        Some(canvas) => ScalingMode::WindowSize(pixel_perfect_scale(
+            canvas.virtual_resolution.as_vec2(),
+            view_size,
+        )),
+        None => ScalingMode::AutoMin {
+            min_width: view_size.x,
+            min_height: view_size.y,
+        },
+    };
+
+    // Avoid triggering change detection of the projection every frame
+    let unchanged = match (projection.scaling_mode, scaling_mode) {
+        (ScalingMode::WindowSize(current), ScalingMode::WindowSize(scale)) => current == scale,
+        (
+            ScalingMode::AutoMin {
+                min_width,
+                min_height,
+            },
+            ScalingMode::AutoMin { .. },
+        ) => min_width == view_size.x && min_height == view_size.y,
+        _ => false,
+    };
+
+    if !unchanged {
+        projection.scaling_mode = scaling_mode;
+    }
+}
+
+/// Canvas pixels per world unit showing at least `view_size`.
+/// Either a whole number, or one over a whole number when the view doesn't fit in the canvas.
+fn pixel_perfect_scale(virtual_resolution: Vec2, view_size: Vec2) -> f32 {
+    let fit = (virtual_resolution / view_size).min_element();
+
+    if fit >= 1. {
+        fit.floor()
+    } else {
+        1. / (1. / fit).ceil()
+    }
+}
+
+fn follow_target(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    tiles: Res<LandTiles>,
+    target: Query<&GlobalTransform, With<CameraTarget>>,
+    mut query: Query<&mut CameraRig, With<MainCamera>>,
+) {
+    let Ok(mut rig) = query.get_single_mut() else {
+        return;
+    };
+
+    let arena = tiles.world_rect();
+
+    let Some(follow) = &settings.follow else {
+        rig.focus = arena.center();
+        return;
+    };
+
+    let Ok(target) = target.get_single() else {
+        return;
+    };
+
+    let target = target.translation().xy();
+    let offset = target - rig.focus;
+    let outside_deadzone = offset - offset.clamp(-follow.deadzone, follow.deadzone);
+
+    // Frame rate independent exponential smoothing
+    let t = 1. - (-follow.smoothing * time.delta_seconds()).exp();
+    let focus = rig.focus + outside_deadzone * t;
+
+    rig.focus = focus.clamp(arena.min, arena.max);
+}
+
+fn apply_camera_rig(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    mut query: Query<
+        (&mut CameraRig, &mut Transform, &mut OrthographicProjection),
+        With<MainCamera>,
+    >,
+) {
+    let Ok((mut rig, mut transform, mut projection)) = query.get_single_mut() else {
+        return;
+    };
+
+    rig.trauma = (rig.trauma - settings.trauma_decay * time.delta_seconds()).max(0.);
+
+    let shake = rig.trauma * rig.trauma;
+    let t = time.elapsed_seconds() + rig.shake_phase;
+    // Sums of sines with unrelated frequencies are good enough noise for the shake
+    let noise = |seed: f32| ((t * 31. + seed).sin() + (t * 47. + seed * 2.3).sin()) / 2.;
+
+    let offset = Vec2::new(noise(0.), noise(10.)) * settings.max_shake_offset * shake;
+    let angle = noise(20.) * settings.max_shake_angle * shake;
+
+    transform.translation = (rig.focus + offset).extend(transform.translation.z);
+    transform.rotation = Quat::from_rotation_z(angle);
+
+    let mut zoom = 1.;
+    if let Some((amount, timer)) = &mut rig.zoom_pulse {
+        timer.tick(time.delta());
+        // Zoom in and back out
+        zoom += *amount * (timer.fraction() * std::f32::consts::PI).sin();
+
+        if timer.finished() {
+            rig.zoom_pulse = None;
+        }
+    }
+
+    let scale = 1. / zoom;
+    if projection.scale != scale {
+        projection.scale = scale;
+    }
+}
@@ -0,0 +1,158 @@
+//! Renders [`MainCamera`] into a low resolution image, which is then drawn to the window
+//! scaled by the largest integer factor that fits, so every game pixel has the same size on screen.
+//! The rest of the window is letterboxed. The arena is framed with a whole number of canvas pixels
+//! per world unit (or world units per pixel), so sprite texels are even too.
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        texture::ImageSampler,
+        view::RenderLayers,
+    },
+    window::PrimaryWindow,
+};
+
+use super::{CameraSettings, MainCamera};
+
+/// Layer on which only the canvas is drawn, so the window camera doesn't see the game world
+const CANVAS_LAYER: usize = 1;
+const LETTERBOX_COLOR: Color = Color::BLACK;
+
+/// Low resolution image [`MainCamera`] renders to
+#[derive(Resource)]
+pub struct PixelPerfectCanvas {
+    pub image: Handle<Image>,
+    pub virtual_resolution: UVec2,
+    /// Area of the window covered by the canvas, in logical pixels from the top left corner
+    pub rect: Rect,
+}
+
+impl PixelPerfectCanvas {
+    /// Maps logical window position to the position in the canvas,
+    /// suitable for [`Camera::viewport_to_world_2d`] of the [`MainCamera`].
+    /// Returns `None` for positions in the letterbox.
+    pub fn window_to_viewport(&self, position: Vec2) -> Option<Vec2> {
+        if !self.rect.contains(position) || self.rect.is_empty() {
+            return None;
+        }
+
+        Some((position - self.rect.min) / self.rect.size() * self.virtual_resolution.as_vec2())
+    }
+}
+
+/// Camera drawing the canvas to the window
+#[derive(Component)]
+pub struct CanvasCamera;
+
+/// Sprite showing the canvas in the [`CanvasCamera`]
+#[derive(Component)]
+pub struct CanvasSprite;
+
+pub(super) fn setup_pixel_perfect(
+    mut commands: Commands,
+    settings: Res<CameraSettings>,
+    mut images: ResMut<Assets<Image>>,
+    mut main_camera: Query<&mut Camera, With<MainCamera>>,
+) {
+    let Some(virtual_resolution) = settings.pixel_perfect else {
+        return;
+    };
+
+    let size = Extent3d {
+        width: virtual_resolution.x,
+        height: virtual_resolution.y,
+        depth_or_array_layers: 1,
+    };
+
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("pixel_perfect_canvas"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        sampler: ImageSampler::nearest(),
+        ..default()
+    };
+    // Fills image with zeroes
+    image.resize(size);
+
+    let image = images.add(image);
+
+    for mut camera in main_camera.iter_mut() {
+        camera.target = RenderTarget::Image(image.clone());
+        // Canvas should be ready before it's drawn to the window
+        camera.order = -1;
+    }
+
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                clear_color: ClearColorConfig::Custom(LETTERBOX_COLOR),
+                ..default()
+            },
+            ..default()
+        },
+        RenderLayers::layer(CANVAS_LAYER),
+        IsDefaultUiCamera,
+        CanvasCamera,
+    ));
+
+    commands.spawn((
+        SpriteBundle {
+            texture: image.clone(),
+            ..default()
+        },
+        RenderLayers::layer(CANVAS_LAYER),
+        CanvasSprite,
+    ));
+
+    commands.insert_resource(PixelPerfectCanvas {
+        image,
+        virtual_resolution,
+        rect: Rect::default(),
+    });
+}
+
+pub(super) fn fit_canvas(
+    mut canvas: ResMut<PixelPerfectCanvas>,
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut sprites: Query<&mut Sprite, With<CanvasSprite>>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let virtual_resolution = canvas.virtual_resolution.as_vec2();
+    let physical_size = window.physical_size().as_vec2();
+
+    // Integer scaling is done in physical pixels, otherwise hidpi screens get uneven pixels again
+    let scale = (physical_size / virtual_resolution)
+        .min_element()
+        .floor()
+        .max(1.);
+    let logical_size = virtual_resolution * scale / window.scale_factor();
+
+    let window_size = window.size();
+    let rect = Rect::from_center_size(window_size / 2., logical_size);
+
+    if canvas.rect != rect {
+        canvas.rect = rect;
+    }
+
+    for mut sprite in sprites.iter_mut() {
+        if sprite.custom_size != Some(logical_size) {
+            sprite.custom_size = Some(logical_size);
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Combinators over `Tick` systems of [`Animation`](super::Animation).
+//!
+//! All of them are [`EntitySystem`]s with `In = f32` themselves, so they can be nested:
+//! `Yoyo<Ease<QuadOut, Parallel<TweenTransform<Translate>, Tint>>>` is a valid `Tick`.
+//!
+//! They are also [`TransformTween`]s when composing transform tweens, so translation, scale and
+//! rotation can be combined over the same transform:
+//! `TweenTransform<Sequence<Parallel<Translate, Scale>, Yoyo<Rotate>>>`.
+//!
+//! For looping animations use [`TimerMode::Repeating`](bevy::prelude::TimerMode::Repeating)
+//! on the animation timer, `Finished` is then run at the end of every loop.
+
+use std::marker::PhantomData;
+
+use bevy::{ecs::query::QueryItem, prelude::*};
+
+use super::{easing::Easing, tween::TransformTween};
+use crate::dynamic_initialization::{DataItem, EntitySystem, ParamItem};
+
+/// Remaps progress of `T` with easing `E`.
+pub struct Ease<E, T>(PhantomData<(E, T)>);
+
+impl<E: Easing, T: EntitySystem<In = f32, Out = ()>> EntitySystem for Ease<E, T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        T::run(E::ease(input), data, param);
+    }
+}
+
+impl<E: Easing, T: TransformTween> TransformTween for Ease<E, T> {
+    type Data = T::Data;
+
+    #[inline]
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        T::apply(E::ease(input), transform, data);
+    }
+}
+
+/// Runs `A` and `B` with the same progress.
+///
+/// As [`EntitySystem`]s `A` and `B` must not access the same data mutably,
+/// so e.g. [`FadeAway`](super::fade_away::FadeAway) can't be run in parallel with [`Tint`](super::tween::Tint).
+/// Transform tweens are composed as [`TransformTween`]s instead.
+pub struct Parallel<A, B>(PhantomData<(A, B)>);
+
+impl<A: EntitySystem<In = f32, Out = ()>, B: EntitySystem<In = f32, Out = ()>> EntitySystem
+    for Parallel<A, B>
+{
+    type Data = (A::Data, B::Data);
+    type Filter = (A::Filter, B::Filter);
+    type Param = (A::Param, B::Param);
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (a_data, b_data) = data;
+        let (a_param, b_param) = param;
+
+        A::run(input, a_data, a_param);
+        B::run(input, b_data, b_param);
+    }
+}
+
+impl<A: TransformTween, B: TransformTween> TransformTween for Parallel<A, B> {
+    type Data = (A::Data, B::Data);
+
+    #[inline]
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        let (a_data, b_data) = data;
+
+        A::apply(input, transform, a_data);
+        B::apply(input, transform, b_data);
+    }
+}
+
+/// Runs `A` during first `SPLIT_PERCENT` percents of the animation and `B` during the rest.
+///
+/// After `A` is done it keeps receiving `1.0`, so it stays in it's final state.
+/// Same restrictions on data access as in [`Parallel`] apply.
+pub struct Sequence<A, B, const SPLIT_PERCENT: u8 = 50>(PhantomData<(A, B)>);
+
+impl<A, B, const SPLIT_PERCENT: u8> Sequence<A, B, SPLIT_PERCENT> {
+    /// Progress of `A` and `B`, `B` isn't run before the split
+    fn inputs(input: f32) -> (f32, Option<f32>) {
+        let split = (SPLIT_PERCENT.min(100) as f32) / 100.;
+
+        if input < split {
+            (input / split, None)
+        } else if split >= 1. {
+            (1., Some(1.))
+        } else {
+            (1., Some((input - split) / (1. - split)))
+        }
+    }
+}
+
+impl<
+        A: EntitySystem<In = f32, Out = ()>,
+        B: EntitySystem<In = f32, Out = ()>,
+        const SPLIT_PERCENT: u8,
+    > EntitySystem for Sequence<A, B, SPLIT_PERCENT>
+{
+    type Data = (A::Data, B::Data);
+    type Filter = (A::Filter, B::Filter);
+    type Param = (A::Param, B::Param);
+
+    type In = f32;
+    type Out = ();
+
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (a_data, b_data) = data;
+        let (a_param, b_param) = param;
+
+        let (a_input, b_input) = Self::inputs(input);
+
+        A::run(a_input, a_data, a_param);
+        if let Some(b_input) = b_input {
+            B::run(b_input, b_data, b_param);
+        }
+    }
+}
+
+impl<A: TransformTween, B: TransformTween, const SPLIT_PERCENT: u8> TransformTween
+    for Sequence<A, B, SPLIT_PERCENT>
+{
+    type Data = (A::Data, B::Data);
+
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        let (a_data, b_data) = data;
+
+        let (a_input, b_input) = Self::inputs(input);
+
+        A::apply(a_input, transform, a_data);
+        if let Some(b_input) = b_input {
+            B::apply(b_input, transform, b_data);
+        }
+    }
+}
+
+/// Plays `T` forward during the first half of the animation and backward during the second.
+pub struct Yoyo<T>(PhantomData<T>);
+
+impl<T> Yoyo<T> {
+    #[inline]
+    fn input(input: f32) -> f32 {
+        if input < 0.5 {
+            input * 2.
+        } else {
+            2. - input * 2.
+        }
+    }
+}
+
+impl<T: EntitySystem<In = f32, Out = ()>> EntitySystem for Yoyo<T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        T::run(Self::input(input), data, param);
+    }
+}
+
+impl<T: TransformTween> TransformTween for Yoyo<T> {
+    type Data = T::Data;
+
+    #[inline]
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        T::apply(Self::input(input), transform, data);
+    }
+}
+
+/// Plays `T` `TIMES` times during one run of the animation.
+pub struct Repeat<T, const TIMES: u32>(PhantomData<T>);
+
+impl<T, const TIMES: u32> Repeat<T, TIMES> {
+    #[inline]
+    fn input(input: f32) -> f32 {
+        // Last frame of the animation should leave `T` in it's final state, not initial
+        if input >= 1. {
+            1.
+        } else {
+            (input * TIMES as f32).fract()
+        }
+    }
+}
+
+impl<T: EntitySystem<In = f32, Out = ()>, const TIMES: u32> EntitySystem for Repeat<T, TIMES> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        T::run(Self::input(input), data, param);
+    }
+}
+
+impl<T: TransformTween, const TIMES: u32> TransformTween for Repeat<T, TIMES> {
+    type Data = T::Data;
+
+    #[inline]
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        T::apply(Self::input(input), transform, data);
+    }
+}
+
+/// Plays `T` backward.
+pub struct Reverse<T>(PhantomData<T>);
+
+impl<T: EntitySystem<In = f32, Out = ()>> EntitySystem for Reverse<T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        T::run(1. - input, data, param);
+    }
+}
+
+impl<T: TransformTween> TransformTween for Reverse<T> {
+    type Data = T::Data;
+
+    #[inline]
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>) {
+        T::apply(1. - input, transform, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
+
+    use super::{Parallel, Sequence};
+    use crate::{
+        common::animation::{
+            tween::{
+                Rotate, Scale, Translate, TweenRotation, TweenScale, TweenTransform,
+                TweenTranslation,
+            },
+            Animation,
+        },
+        dynamic_initialization::DynamicInitializationPlugin,
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, DynamicInitializationPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app
+    }
+
+    fn tweens() -> impl Bundle {
+        (
+            Transform::default(),
+            TweenTranslation::new(Vec3::ZERO, Vec3::X),
+            TweenScale::new(Vec3::ONE, Vec3::splat(2.)),
+            TweenRotation::new(0., 1.),
+        )
+    }
+
+    #[test]
+    fn parallel_transform_tweens_share_transform() {
+        let mut app = app();
+        let entity = app
+            .world_mut()
+            .spawn((
+                tweens(),
+                Animation::<TweenTransform<Parallel<Translate, Parallel<Scale, Rotate>>>, ()>::new(
+                    Timer::from_seconds(1., TimerMode::Once),
+                ),
+            ))
+            .id();
+
+        for _ in 0..20 {
+            app.update();
+        }
+
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::X);
+        assert_eq!(transform.scale, Vec3::splat(2.));
+        assert_eq!(transform.rotation, Quat::from_rotation_z(1.));
+    }
+
+    #[test]
+    fn sequence_of_transform_tweens_runs_in_order() {
+        let mut app = app();
+        let entity = app
+            .world_mut()
+            .spawn((
+                tweens(),
+                Animation::<TweenTransform<Sequence<Translate, Scale>>, ()>::new(
+                    Timer::from_seconds(1., TimerMode::Once),
+                ),
+            ))
+            .id();
+
+        let mut translated_before_scaled = false;
+        for _ in 0..20 {
+            app.update();
+
+            let transform = app.world().get::<Transform>(entity).unwrap();
+            if transform.translation == Vec3::X && transform.scale == Vec3::ONE {
+                translated_before_scaled = true;
+            }
+        }
+
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert!(translated_before_scaled);
+        assert_eq!(transform.translation, Vec3::X);
+        assert_eq!(transform.scale, Vec3::splat(2.));
+    }
+}
@@ -37,3 +37,47 @@ impl<Tick: EntitySystem<In = f32, Out = ()>, Marker: Send + Sync + 'static> Enti
         animation.disable();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{prelude::*, time::TimeUpdateStrategy, utils::Duration};
+
+    use super::Disable;
+    use crate::{
+        common::animation::{
+            tween::{Translate, TweenTransform, TweenTranslation},
+            Animation,
+        },
+        dynamic_initialization::DynamicInitializationPlugin,
+    };
+
+    type TranslateAnimation =
+        Animation<TweenTransform<Translate>, Disable<TweenTransform<Translate>>>;
+
+    #[test]
+    fn finished_animation_disables_itself() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, DynamicInitializationPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+
+        let entity = app
+            .world_mut()
+            .spawn((
+                Transform::default(),
+                TweenTranslation::new(Vec3::ZERO, Vec3::X),
+                TranslateAnimation::new(Timer::from_seconds(1., TimerMode::Once)),
+            ))
+            .id();
+
+        for _ in 0..20 {
+            app.update();
+        }
+
+        let animation = app.world().get::<TranslateAnimation>(entity).unwrap();
+        assert!(!animation.is_enabled());
+        let transform = app.world().get::<Transform>(entity).unwrap();
+        assert_eq!(transform.translation, Vec3::X);
+    }
+}
@@ -0,0 +1,160 @@
+use std::f32::consts::PI;
+
+/// Maps linear animation progress in `0.0..=1.0` to eased progress.
+///
+/// Result is 0 at 0 and 1 at 1, but may overshoot in between (see [`BackOut`], [`ElasticOut`]).
+pub trait Easing: Send + Sync + 'static {
+    fn ease(t: f32) -> f32;
+}
+
+// Overshoot constants from https://easings.net
+const BACK_C1: f32 = 1.70158;
+const BACK_C2: f32 = BACK_C1 * 1.525;
+const BACK_C3: f32 = BACK_C1 + 1.;
+const ELASTIC_C4: f32 = (2. * PI) / 3.;
+const ELASTIC_C5: f32 = (2. * PI) / 4.5;
+
+pub struct Linear;
+
+impl Easing for Linear {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        t
+    }
+}
+
+pub struct QuadIn;
+
+impl Easing for QuadIn {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        t * t
+    }
+}
+
+pub struct QuadOut;
+
+impl Easing for QuadOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        1. - (1. - t) * (1. - t)
+    }
+}
+
+pub struct QuadInOut;
+
+impl Easing for QuadInOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t < 0.5 {
+            2. * t * t
+        } else {
+            1. - (-2. * t + 2.).powi(2) / 2.
+        }
+    }
+}
+
+pub struct CubicIn;
+
+impl Easing for CubicIn {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        t * t * t
+    }
+}
+
+pub struct CubicOut;
+
+impl Easing for CubicOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        1. - (1. - t).powi(3)
+    }
+}
+
+pub struct CubicInOut;
+
+impl Easing for CubicInOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t < 0.5 {
+            4. * t * t * t
+        } else {
+            1. - (-2. * t + 2.).powi(3) / 2.
+        }
+    }
+}
+
+pub struct BackIn;
+
+impl Easing for BackIn {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        BACK_C3 * t * t * t - BACK_C1 * t * t
+    }
+}
+
+pub struct BackOut;
+
+impl Easing for BackOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        1. + BACK_C3 * (t - 1.).powi(3) + BACK_C1 * (t - 1.).powi(2)
+    }
+}
+
+pub struct BackInOut;
+
+impl Easing for BackInOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t < 0.5 {
+            ((2. * t).powi(2) * ((BACK_C2 + 1.) * 2. * t - BACK_C2)) / 2.
+        } else {
+            ((2. * t - 2.).powi(2) * ((BACK_C2 + 1.) * (t * 2. - 2.) + BACK_C2) + 2.) / 2.
+        }
+    }
+}
+
+pub struct ElasticIn;
+
+impl Easing for ElasticIn {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t <= 0. || t >= 1. {
+            return t.clamp(0., 1.);
+        }
+
+        -(2f32.powf(10. * t - 10.)) * ((t * 10. - 10.75) * ELASTIC_C4).sin()
+    }
+}
+
+pub struct ElasticOut;
+
+impl Easing for ElasticOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t <= 0. || t >= 1. {
+            return t.clamp(0., 1.);
+        }
+
+        2f32.powf(-10. * t) * ((t * 10. - 0.75) * ELASTIC_C4).sin() + 1.
+    }
+}
+
+pub struct ElasticInOut;
+
+impl Easing for ElasticInOut {
+    #[inline]
+    fn ease(t: f32) -> f32 {
+        if t <= 0. || t >= 1. {
+            return t.clamp(0., 1.);
+        }
+
+        if t < 0.5 {
+            -(2f32.powf(20. * t - 10.) * ((20. * t - 11.125) * ELASTIC_C5).sin()) / 2.
+        } else {
+            (2f32.powf(-20. * t + 10.) * ((20. * t - 11.125) * ELASTIC_C5).sin()) / 2. + 1.
+        }
+    }
+}
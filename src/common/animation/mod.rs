@@ -1,14 +1,23 @@
 use crate::dynamic_initialization::{
     DynamicallyInitializedComponentHooks, DynamicallyInitializedSystems, EntitySystem,
-    ScheduleToAddSystems,
 };
-use bevy::{ecs::component::StorageType, prelude::*};
+use bevy::{
+    ecs::{
+        component::StorageType,
+        schedule::{InternedScheduleLabel, ScheduleLabel, SystemConfigs},
+    },
+    prelude::*,
+    utils::Duration,
+};
 use std::marker::PhantomData;
 
+pub mod compose;
 pub mod destroy;
 pub mod disable;
+pub mod easing;
 pub mod fade_away;
 pub mod show_up;
+pub mod tween;
 
 pub struct AnimationPlugin;
 
@@ -45,9 +54,9 @@ impl<
         Marker: Send + Sync + 'static,
     > DynamicallyInitializedSystems for Animation<Tick, Finished, Marker>
 {
-    fn systems() -> (ScheduleToAddSystems, bevy::ecs::schedule::SystemConfigs) {
+    fn systems() -> (InternedScheduleLabel, SystemConfigs) {
         (
-            ScheduleToAddSystems::Update,
+            Update.intern(),
             (
                 tick_animation::<Tick, Finished>,
                 tick_system_animation::<Tick, Finished>,
@@ -74,12 +83,17 @@ impl<
 
     pub fn new_disabled(timer: Timer) -> Self {
         Self {
-            disabled: false,
+            disabled: true,
             timer,
             _pd: PhantomData,
         }
     }
 
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
     #[inline]
     pub fn disable(&mut self) {
         self.disabled = true;
@@ -90,6 +104,11 @@ impl<
         self.disabled = false;
         self.timer.reset();
     }
+
+    #[inline]
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.timer.set_duration(duration);
+    }
 }
 
 fn tick_animation<
@@ -124,17 +143,31 @@ fn animation_finished<
     Tick: EntitySystem<In = f32, Out = ()>,
     Finished: EntitySystem<In = (), Out = ()>,
 >(
-    mut query: Query<(&Animation<Tick, Finished>, Finished::Data), Finished::Filter>,
+    mut finished: Local<Vec<Entity>>,
+    // `Finished` may change the animation itself, like `Disable` does
+    mut queries: ParamSet<(
+        Query<(Entity, &Animation<Tick, Finished>)>,
+        Query<Finished::Data, Finished::Filter>,
+    )>,
     mut param: ParamSet<(Finished::Param,)>,
 ) {
-    for (timer, data) in query.iter_mut() {
-        // It's a bug if timer finished several times this tick
-        // But I can't put this system in a loop, because `run` requires ovnership of the data
-        // And there is no such trait as Reborrow so that I can reborrow data safely
-        // (I tried creating one in utils module, look at it if you interested :))
-        //
-        // For game jam this bug is negligible
-        if timer.timer.just_finished() {
+    // It's a bug if timer finished several times this tick
+    // But I can't put this system in a loop, because `run` requires ovnership of the data
+    // And there is no such trait as Reborrow so that I can reborrow data safely
+    // (I tried creating one in utils module, look at it if you interested :))
+    //
+    // For game jam this bug is negligible
+    finished.extend(
+        queries
+            .p0()
+            .iter()
+            .filter(|(_, timer)| timer.timer.just_finished())
+            .map(|(entity, _)| entity),
+    );
+
+    let mut query = queries.p1();
+    for entity in finished.drain(..) {
+        if let Ok(data) = query.get_mut(entity) {
             Finished::run((), data, param.p0());
         }
     }
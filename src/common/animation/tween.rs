@@ -0,0 +1,175 @@
+//! `Tick` systems interpolating entity values between `start` and `end`.
+//!
+//! Each tick reads it's endpoints from the corresponding component on the entity,
+//! e.g. [`Translate`] requires [`TweenTranslation`].
+//!
+//! Translation, scale and rotation are [`TransformTween`]s, they are composed with each other
+//! and then run over a single `&mut Transform` by [`TweenTransform`]:
+//!
+//! ```ignore
+//! commands.spawn((
+//!     SpriteBundle::default(),
+//!     TweenTranslation::new(Vec3::ZERO, Vec3::new(0., 20., 0.)),
+//!     TweenScale::new(Vec3::ONE, Vec3::splat(2.)),
+//!     Animation::<TweenTransform<Ease<BackOut, Parallel<Translate, Scale>>>, ()>::new(
+//!         Timer::new(duration, TimerMode::Once),
+//!     ),
+//! ));
+//! ```
+
+use std::marker::PhantomData;
+
+use bevy::{
+    color::Mix,
+    ecs::query::{QueryItem, ReadOnlyQueryData},
+    prelude::*,
+};
+
+use crate::dynamic_initialization::{DataItem, EntitySystem, ParamItem};
+
+/// Tween writing to the [`Transform`].
+///
+/// Unlike [`EntitySystem`]s, any of them can be composed with each other,
+/// since the transform isn't part of their data.
+pub trait TransformTween: Send + Sync + 'static {
+    /// Endpoints of the tween
+    type Data: ReadOnlyQueryData;
+
+    fn apply(input: f32, transform: &mut Transform, data: QueryItem<'_, Self::Data>);
+}
+
+/// `Tick` running `T` over the transform of the entity
+pub struct TweenTransform<T>(PhantomData<T>);
+
+impl<T: TransformTween> EntitySystem for TweenTransform<T> {
+    type Data = (&'static mut Transform, T::Data);
+    type Filter = ();
+    type Param = ();
+
+    type In = f32;
+    type Out = ();
+
+    #[inline]
+    fn run(input: Self::In, data: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+        let (mut transform, data) = data;
+
+        T::apply(input, &mut transform, data);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct TweenTranslation {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl TweenTranslation {
+    #[inline]
+    pub fn new(start: Vec3, end: Vec3) -> Self {
+        Self { start, end }
+    }
+}
+
+pub struct Translate;
+
+impl TransformTween for Translate {
+    type Data = &'static TweenTranslation;
+
+    fn apply(input: f32, transform: &mut Transform, tween: &TweenTranslation) {
+        transform.translation = tween.start.lerp(tween.end, input);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct TweenScale {
+    pub start: Vec3,
+    pub end: Vec3,
+}
+
+impl TweenScale {
+    #[inline]
+    pub fn new(start: Vec3, end: Vec3) -> Self {
+        Self { start, end }
+    }
+}
+
+pub struct Scale;
+
+impl TransformTween for Scale {
+    type Data = &'static TweenScale;
+
+    fn apply(input: f32, transform: &mut Transform, tween: &TweenScale) {
+        transform.scale = tween.start.lerp(tween.end, input);
+    }
+}
+
+/// Rotation around z axis, angles are in radians
+#[derive(Component, Clone, Copy)]
+pub struct TweenRotation {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl TweenRotation {
+    #[inline]
+    pub fn new(start: f32, end: f32) -> Self {
+        Self { start, end }
+    }
+}
+
+pub struct Rotate;
+
+impl TransformTween for Rotate {
+    type Data = &'static TweenRotation;
+
+    fn apply(input: f32, transform: &mut Transform, tween: &TweenRotation) {
+        let angle = tween.start + (tween.end - tween.start) * input;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct TweenColor {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl TweenColor {
+    #[inline]
+    pub fn new(start: Color, end: Color) -> Self {
+        Self { start, end }
+    }
+}
+
+/// Same as [`FadeAway`](super::fade_away::FadeAway), works both with sprites and color materials
+pub struct Tint;
+
+impl EntitySystem for Tint {
+    type Data = (
+        &'static TweenColor,
+        Option<&'static mut Sprite>,
+        Option<&'static Handle<ColorMaterial>>,
+    );
+    type Filter = ();
+    type Param = ResMut<'static, Assets<ColorMaterial>>;
+
+    type In = f32;
+    type Out = ();
+
+    fn run(input: Self::In, data: DataItem<'_, Self>, param: ParamItem<'_, '_, Self>) {
+        let (tween, sprite, material_handle) = data;
+        let mut materials = param;
+
+        let new_color = tween.start.mix(&tween.end, input);
+
+        if let Some(mut sprite) = sprite {
+            sprite.color = new_color;
+        }
+
+        if let Some(material_handle) = material_handle {
+            if let Some(material) = materials.get_mut(material_handle) {
+                material.color = new_color;
+            }
+        }
+    }
+}
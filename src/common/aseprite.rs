@@ -0,0 +1,212 @@
+//! Loads `.aseprite` files into a sprite sheet, it's [`TextureAtlasLayout`] and [`FrameClips`].
+//!
+//! Every tag of the file becomes a clip with the same name and per-frame durations from the file.
+//! Files without tags get a single looping [`clips::IDLE`] clip with all the frames.
+//!
+//! Parts of the file can be loaded directly with labels:
+//!
+//! ```ignore
+//! let texture: Handle<Image> = asset_server.load("textures/player.aseprite#image");
+//! let layout: Handle<TextureAtlasLayout> = asset_server.load("textures/player.aseprite#layout");
+//! let clips: Handle<FrameClips> = asset_server.load("textures/player.aseprite#clips");
+//! ```
+
+use std::fmt;
+
+use asefile::{AnimationDirection, AsepriteFile, AsepriteParseError};
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+    utils::Duration,
+};
+
+use super::frame_animation::{clips, FrameClip, FrameClips};
+
+pub struct AsepritePlugin;
+
+impl Plugin for AsepritePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Aseprite>()
+            .init_asset_loader::<AsepriteLoader>();
+    }
+}
+
+pub const IMAGE_LABEL: &str = "image";
+pub const LAYOUT_LABEL: &str = "layout";
+pub const CLIPS_LABEL: &str = "clips";
+
+/// All frames of the file are laid out left to right in `image`
+#[derive(Asset, TypePath)]
+pub struct Aseprite {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: Handle<FrameClips>,
+}
+
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+#[derive(Debug)]
+pub enum AsepriteLoaderError {
+    Io(std::io::Error),
+    Parse(AsepriteParseError),
+}
+
+impl fmt::Display for AsepriteLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AsepriteLoaderError::Io(err) => write!(f, "Could not read aseprite file: {err}"),
+            AsepriteLoaderError::Parse(err) => write!(f, "Could not parse aseprite file: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for AsepriteLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AsepriteLoaderError::Io(err) => Some(err),
+            AsepriteLoaderError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for AsepriteLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        AsepriteLoaderError::Io(err)
+    }
+}
+
+impl From<AsepriteParseError> for AsepriteLoaderError {
+    fn from(err: AsepriteParseError) -> Self {
+        AsepriteLoaderError::Parse(err)
+    }
+}
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = Aseprite;
+    type Settings = ();
+    type Error = AsepriteLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let ase = AsepriteFile::read(bytes.as_slice())?;
+
+        let image = load_context.add_labeled_asset(IMAGE_LABEL.to_string(), sprite_sheet(&ase));
+        let layout = load_context.add_labeled_asset(
+            LAYOUT_LABEL.to_string(),
+            TextureAtlasLayout::from_grid(
+                UVec2::new(ase.width() as u32, ase.height() as u32),
+                ase.num_frames(),
+                1,
+                None,
+                None,
+            ),
+        );
+        let clips = load_context.add_labeled_asset(CLIPS_LABEL.to_string(), frame_clips(&ase));
+
+        Ok(Aseprite {
+            image,
+            layout,
+            clips,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite", "ase"]
+    }
+}
+
+fn sprite_sheet(ase: &AsepriteFile) -> Image {
+    let (frame_width, frame_height) = ase.size();
+    let frames = ase.num_frames() as usize;
+
+    // Rgba8
+    const PIXEL_SIZE: usize = 4;
+    let row_size = frame_width * frames * PIXEL_SIZE;
+    let frame_row_size = frame_width * PIXEL_SIZE;
+
+    let mut data = vec![0; row_size * frame_height];
+
+    for frame in 0..frames {
+        let frame_image = ase.frame(frame as u32).image();
+        let frame_data = frame_image.as_raw();
+
+        for y in 0..frame_height {
+            let from = y * frame_row_size;
+            let to = y * row_size + frame * frame_row_size;
+
+            data[to..to + frame_row_size].copy_from_slice(&frame_data[from..from + frame_row_size]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: (frame_width * frames) as u32,
+            height: frame_height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::default(),
+    )
+}
+
+fn frame_clips(ase: &AsepriteFile) -> FrameClips {
+    let frame_duration = |frame: u32| Duration::from_millis(ase.frame(frame).duration() as u64);
+
+    if ase.num_tags() == 0 {
+        let mut clip = FrameClip::new(true);
+        for frame in 0..ase.num_frames() {
+            clip = clip.with_frame(frame as usize, frame_duration(frame));
+        }
+
+        return FrameClips::default().with_clip(clips::IDLE, clip);
+    }
+
+    let mut frame_clips = FrameClips::default();
+
+    for tag_id in 0..ase.num_tags() {
+        let tag = ase.tag(tag_id);
+
+        let forward = tag.from_frame()..=tag.to_frame();
+        let order: Vec<u32> = match tag.animation_direction() {
+            AnimationDirection::Forward => forward.collect(),
+            AnimationDirection::Reverse => forward.rev().collect(),
+            // Aseprite doesn't repeat first and last frames when going back
+            AnimationDirection::PingPong => forward
+                .clone()
+                .chain(
+                    forward
+                        .rev()
+                        .skip(1)
+                        .take((tag.to_frame() - tag.from_frame()).saturating_sub(1) as usize),
+                )
+                .collect(),
+        };
+
+        // Tag without repeat count is looping forever
+        let mut clip = FrameClip::new(tag.repeat().is_none());
+        let repeat = tag.repeat().map_or(1, |repeat| repeat.get());
+        for _ in 0..repeat {
+            for &frame in &order {
+                clip = clip.with_frame(frame as usize, frame_duration(frame));
+            }
+        }
+
+        frame_clips.insert(tag.name().to_string(), clip);
+    }
+
+    frame_clips
+}
@@ -1,3 +1,6 @@
+// `PhysicsLayer` derive checks for avian's own `2d`/`3d` features in our crate
+#![allow(unexpected_cfgs)]
+
 use std::ops::Not;
 
 use avian2d::prelude::*;
@@ -11,7 +14,9 @@ impl Plugin for CollidersPlugin {
     }
 }
 
-#[derive(Clone, Copy)]
+/// Side of the actor. Characters get it as a component in
+/// [`character_with_hurtbox`](CollidersCommands::character_with_hurtbox)
+#[derive(Component, Clone, Copy, PartialEq, Eq, Reflect, Debug)]
 pub enum Alignment {
     Player,
     Enemy,
@@ -51,6 +56,7 @@ impl Not for Alignment {
 pub enum CollisionMask {
     Character,
     Projectile,
+    Wall,
 
     PlayerHitbox,
     EnemyHitbox,
@@ -71,13 +77,22 @@ pub trait CollidersCommands {
 
     fn with_hurtbox(&mut self, alignment: Alignment, hurtbox: Collider) -> &mut Self;
 
-    /// Characters collide with walls (don't pass through them).
-    /// Dynamic Rigidbodies
+    /// Characters collide with walls (don't pass through them) and push each other.
+    /// Kinematic sensors: `resolve_walls` in [`walls`](super::walls) moves them out of walls,
+    /// `push_characters` in [`hits`](super::hits) pushes them apart
     fn character_with_hurtbox(&mut self, alignment: Alignment, hurtbox: Collider) -> &mut Self;
 
     /// Projectiles detect collisoions with walls (may pass through them).
     /// Kinematic Rigidbodies
     fn projectile_with_hitbox(&mut self, alignment: Alignment, hitbox: Collider) -> &mut Self;
+
+    /// Uses [`DisableColliderOnTimer`]
+    fn projectile_with_hitbox_for(
+        &mut self,
+        alignment: Alignment,
+        hitbox: Collider,
+        hitbox_lifetime: Duration,
+    ) -> &mut Self;
 }
 
 impl CollidersCommands for EntityCommands<'_> {
@@ -129,7 +144,11 @@ impl CollidersCommands for EntityCommands<'_> {
     fn character_with_hurtbox(&mut self, alignment: Alignment, hurtbox: Collider) -> &mut Self {
         let hurtbox_collision_layers = CollisionLayers::new(
             [CollisionMask::Character, alignment.hurtbox()],
-            (!alignment).hitbox(),
+            [
+                CollisionMask::Character,
+                CollisionMask::Wall,
+                (!alignment).hitbox(),
+            ],
         );
 
         self.insert((
@@ -137,13 +156,14 @@ impl CollidersCommands for EntityCommands<'_> {
             hurtbox,
             Sensor,
             hurtbox_collision_layers,
+            alignment,
         ))
     }
 
     fn projectile_with_hitbox(&mut self, alignment: Alignment, hitbox: Collider) -> &mut Self {
         let hitbox_collision_layers = CollisionLayers::new(
             [CollisionMask::Projectile, alignment.hitbox()],
-            (!alignment).hurtbox(),
+            [CollisionMask::Wall, (!alignment).hurtbox()],
         );
 
         self.insert((
@@ -153,6 +173,18 @@ impl CollidersCommands for EntityCommands<'_> {
             hitbox_collision_layers,
         ))
     }
+
+    fn projectile_with_hitbox_for(
+        &mut self,
+        alignment: Alignment,
+        hitbox: Collider,
+        hitbox_lifetime: Duration,
+    ) -> &mut Self {
+        self.projectile_with_hitbox(alignment, hitbox)
+            .insert(DisableColliderOnTimer {
+                timer: Timer::new(hitbox_lifetime, TimerMode::Once),
+            })
+    }
 }
 
 /// Disales collider by switching it's layers to [`CollisionLayers::NONE`]
@@ -0,0 +1,361 @@
+use std::borrow::Cow;
+
+use bevy::{
+    prelude::*,
+    utils::{Duration, HashMap},
+};
+
+pub struct FrameAnimationPlugin;
+
+impl Plugin for FrameAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<FrameClips>()
+            .add_event::<FrameEvent>()
+            .add_systems(Update, tick_frame_animation);
+    }
+}
+
+/// Clip names shared between actors, so behaviours can switch clips without knowing the actor
+pub mod clips {
+    pub const IDLE: &str = "idle";
+    pub const WALK: &str = "walk";
+    pub const ATTACK_WINDUP: &str = "attack_windup";
+    pub const FALL: &str = "fall";
+}
+
+#[derive(Clone)]
+pub struct Frame {
+    /// Index in the [`TextureAtlas`]
+    pub index: usize,
+    pub duration: Duration,
+    /// Name of the [`FrameEvent`] sent when this frame is shown
+    pub event: Option<Cow<'static, str>>,
+}
+
+#[derive(Clone, Default)]
+pub struct FrameClip {
+    pub frames: Vec<Frame>,
+    pub looping: bool,
+    /// Clip to switch to when this one is finished. Ignored for looping clips.
+    pub next: Option<Cow<'static, str>>,
+}
+
+impl FrameClip {
+    #[inline]
+    pub fn new(looping: bool) -> Self {
+        Self {
+            frames: Vec::new(),
+            looping,
+            next: None,
+        }
+    }
+
+    /// Clip playing atlas indices from `indices` in order, each for `frame_duration`
+    pub fn from_indices(
+        indices: impl IntoIterator<Item = usize>,
+        frame_duration: Duration,
+        looping: bool,
+    ) -> Self {
+        let mut clip = Self::new(looping);
+        for index in indices {
+            clip = clip.with_frame(index, frame_duration);
+        }
+        clip
+    }
+
+    pub fn with_frame(mut self, index: usize, duration: Duration) -> Self {
+        self.frames.push(Frame {
+            index,
+            duration,
+            event: None,
+        });
+        self
+    }
+
+    pub fn with_event_frame(
+        mut self,
+        index: usize,
+        duration: Duration,
+        event: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.frames.push(Frame {
+            index,
+            duration,
+            event: Some(event.into()),
+        });
+        self
+    }
+
+    pub fn then(mut self, next: impl Into<Cow<'static, str>>) -> Self {
+        self.next = Some(next.into());
+        self
+    }
+}
+
+/// Named clips of one sprite sheet
+#[derive(Asset, TypePath, Default)]
+pub struct FrameClips {
+    clips: HashMap<Cow<'static, str>, FrameClip>,
+}
+
+impl FrameClips {
+    pub fn with_clip(mut self, name: impl Into<Cow<'static, str>>, clip: FrameClip) -> Self {
+        self.insert(name, clip);
+        self
+    }
+
+    #[inline]
+    pub fn insert(&mut self, name: impl Into<Cow<'static, str>>, clip: FrameClip) {
+        self.clips.insert(name.into(), clip);
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&FrameClip> {
+        self.clips.get(name)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FrameClip)> {
+        self.clips.iter().map(|(name, clip)| (name.as_ref(), clip))
+    }
+}
+
+/// Drives index of the [`TextureAtlas`] on the same entity
+#[derive(Component)]
+pub struct FrameAnimation {
+    clips: Handle<FrameClips>,
+    clip: Cow<'static, str>,
+    frame: usize,
+    elapsed: Duration,
+    started: bool,
+    finished: bool,
+}
+
+impl FrameAnimation {
+    pub fn new(clips: Handle<FrameClips>, clip: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            clips,
+            clip: clip.into(),
+            frame: 0,
+            elapsed: Duration::ZERO,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Switches to the `clip`. Does nothing if `clip` is already playing,
+    /// so it's fine to call it every tick from behaviours.
+    pub fn play(&mut self, clip: impl Into<Cow<'static, str>>) {
+        let clip = clip.into();
+        if self.clip != clip {
+            self.restart(clip);
+        }
+    }
+
+    /// Switches to the `clip` and plays it from the first frame.
+    pub fn restart(&mut self, clip: impl Into<Cow<'static, str>>) {
+        self.clip = clip.into();
+        self.frame = 0;
+        self.elapsed = Duration::ZERO;
+        self.started = false;
+        self.finished = false;
+    }
+
+    #[inline]
+    pub fn clip(&self) -> &str {
+        &self.clip
+    }
+
+    #[inline]
+    pub fn clips(&self) -> &Handle<FrameClips> {
+        &self.clips
+    }
+
+    /// True if non-looping clip has shown it's last frame for it's full duration
+    #[inline]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// True while a non-looping clip, like [`clips::FALL`], hasn't finished yet
+    pub fn playing_once(&self, clips: &Assets<FrameClips>) -> bool {
+        !self.finished
+            && clips
+                .get(&self.clips)
+                .and_then(|clips| clips.get(&self.clip))
+                .is_some_and(|clip| !clip.looping)
+    }
+}
+
+/// Sent when frame with [`Frame::event`] is shown
+#[derive(Event, Clone)]
+pub struct FrameEvent {
+    pub entity: Entity,
+    pub name: Cow<'static, str>,
+}
+
+pub(super) fn tick_frame_animation(
+    time: Res<Time>,
+    clips: Res<Assets<FrameClips>>,
+    mut query: Query<(Entity, &mut FrameAnimation, &mut TextureAtlas)>,
+    mut events: EventWriter<FrameEvent>,
+) {
+    for (entity, mut animation, mut atlas) in query.iter_mut() {
+        if animation.finished {
+            continue;
+        }
+
+        let Some(clips) = clips.get(&animation.clips) else {
+            continue;
+        };
+
+        let animation = &mut *animation;
+        let Some(mut clip) = clips.get(&animation.clip) else {
+            warn_once!(
+                "Clip `{}` of {entity} is missing in it's sheet, the animation is stopped",
+                animation.clip
+            );
+            continue;
+        };
+
+        if !animation.started {
+            animation.started = true;
+            show_frame(entity, clip, animation.frame, &mut atlas, &mut events);
+        } else {
+            animation.elapsed += time.delta();
+        }
+
+        while let Some(frame) = clip.frames.get(animation.frame) {
+            // Zero length frames would loop forever
+            if frame.duration.is_zero() || animation.elapsed < frame.duration {
+                break;
+            }
+
+            animation.elapsed -= frame.duration;
+            animation.frame += 1;
+
+            if animation.frame >= clip.frames.len() {
+                if clip.looping {
+                    animation.frame = 0;
+                } else if let Some(next) = clip.next.as_ref().and_then(|next| clips.get(next)) {
+                    animation.clip = clip.next.clone().unwrap();
+                    animation.frame = 0;
+                    clip = next;
+                } else {
+                    animation.frame = clip.frames.len() - 1;
+                    animation.finished = true;
+                    break;
+                }
+            }
+
+            show_frame(entity, clip, animation.frame, &mut atlas, &mut events);
+        }
+    }
+}
+
+fn show_frame(
+    entity: Entity,
+    clip: &FrameClip,
+    frame: usize,
+    atlas: &mut TextureAtlas,
+    events: &mut EventWriter<FrameEvent>,
+) {
+    let Some(frame) = clip.frames.get(frame) else {
+        return;
+    };
+
+    atlas.index = frame.index;
+
+    if let Some(name) = &frame.event {
+        events.send(FrameEvent {
+            entity,
+            name: name.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            // Several ticks every frame of the clips
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME / 4))
+            .init_resource::<Assets<FrameClips>>()
+            .add_event::<FrameEvent>()
+            .add_systems(Update, tick_frame_animation);
+        app
+    }
+
+    fn spawn(app: &mut App, clips: FrameClips, clip: &'static str) -> Entity {
+        let clips = app
+            .world_mut()
+            .resource_mut::<Assets<FrameClips>>()
+            .add(clips);
+
+        app.world_mut()
+            .spawn((
+                FrameAnimation::new(clips, clip),
+                TextureAtlas {
+                    layout: Handle::default(),
+                    index: 0,
+                },
+            ))
+            .id()
+    }
+
+    #[test]
+    fn one_shot_clip_plays_once_until_next_clip() {
+        let mut app = app();
+        let entity = spawn(
+            &mut app,
+            FrameClips::default()
+                .with_clip(clips::IDLE, FrameClip::from_indices([0], FRAME, true))
+                .with_clip(
+                    clips::ATTACK_WINDUP,
+                    FrameClip::from_indices([1, 2], FRAME, false).then(clips::IDLE),
+                ),
+            clips::IDLE,
+        );
+        app.update();
+
+        let playing_once = |app: &App| {
+            let clips = app.world().resource::<Assets<FrameClips>>();
+            let animation = app.world().get::<FrameAnimation>(entity).unwrap();
+            (animation.playing_once(clips), animation.clip().to_owned())
+        };
+        assert_eq!(playing_once(&app), (false, clips::IDLE.to_owned()));
+
+        app.world_mut()
+            .get_mut::<FrameAnimation>(entity)
+            .unwrap()
+            .restart(clips::ATTACK_WINDUP);
+
+        // Movement clips are only played while nothing is played once
+        let mut ticks_playing_once = 0;
+        for _ in 0..20 {
+            app.update();
+
+            match playing_once(&app) {
+                (true, clip) => {
+                    assert_eq!(clip, clips::ATTACK_WINDUP);
+                    ticks_playing_once += 1;
+                }
+                (false, clip) => {
+                    assert_eq!(clip, clips::IDLE);
+                    break;
+                }
+            }
+        }
+
+        // Both frames of the clip are shown before going back to idle
+        assert!((7..=8).contains(&ticks_playing_once));
+        assert_eq!(playing_once(&app), (false, clips::IDLE.to_owned()));
+    }
+}
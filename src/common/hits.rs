@@ -0,0 +1,201 @@
+//! Hits of hitboxes on hurtboxes, knockback and pushing of characters.
+//!
+//! All colliders are kinematic sensors, so physics doesn't move anything by itself.
+//! Knockback is applied as [`StatusEffect::knockback`] overriding the steering,
+//! overlapping characters are pushed apart. Both are scaled by [`Weight`].
+
+use avian2d::prelude::*;
+use bevy::{prelude::*, utils::Duration};
+use bevy_consumable_event::ConsumableEventWriter;
+
+use super::colliders::CollisionMask;
+use crate::status_effects::{ApplyStatusEffect, StatusEffect};
+
+pub struct HitsPlugin;
+
+impl Plugin for HitsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Hit>()
+            .add_systems(Update, (detect_hits, apply_knockback).chain())
+            .add_systems(
+                PostUpdate,
+                push_characters
+                    .after(PhysicsSet::Sync)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// Weight of entities without [`Weight`]
+const DEFAULT_WEIGHT: f32 = 1.0;
+/// Lighter weights are clamped, so knockback and pushing stay finite
+const MIN_WEIGHT: f32 = 0.01;
+
+/// Heavier characters are knocked back and pushed less
+#[derive(Component, Clone, Copy)]
+pub struct Weight(pub f32);
+
+impl Weight {
+    /// Weight of the entity, clamped to [`MIN_WEIGHT`]
+    #[inline]
+    fn of(weight: Option<&Weight>) -> f32 {
+        weight
+            .map_or(DEFAULT_WEIGHT, |weight| weight.0)
+            .max(MIN_WEIGHT)
+    }
+}
+
+/// Hitbox knocks hurtboxes away from it's centre
+#[derive(Component, Clone, Copy)]
+pub struct Knockback {
+    /// Initial speed given to a hurtbox with weight of `1.0`
+    pub strength: f32,
+    pub duration: Duration,
+}
+
+/// Hitbox started colliding with hurtbox
+#[derive(Event, Clone, Copy)]
+pub struct Hit {
+    pub hitbox: Entity,
+    pub hurtbox: Entity,
+}
+
+fn detect_hits(
+    mut collisions: EventReader<CollisionStarted>,
+    layers: Query<&CollisionLayers>,
+    mut hits: EventWriter<Hit>,
+) {
+    let hitboxes = LayerMask::from([CollisionMask::PlayerHitbox, CollisionMask::EnemyHitbox]);
+    let hurtboxes = LayerMask::from([CollisionMask::PlayerHurtbox, CollisionMask::EnemyHurtbox]);
+
+    for CollisionStarted(a, b) in collisions.read() {
+        for (hitbox, hurtbox) in [(*a, *b), (*b, *a)] {
+            let (Ok(hitbox_layers), Ok(hurtbox_layers)) = (layers.get(hitbox), layers.get(hurtbox))
+            else {
+                continue;
+            };
+
+            if hitbox_layers.memberships & hitboxes != LayerMask::NONE
+                && hurtbox_layers.memberships & hurtboxes != LayerMask::NONE
+            {
+                hits.send(Hit { hitbox, hurtbox });
+            }
+        }
+    }
+}
+
+fn apply_knockback(
+    mut hits: EventReader<Hit>,
+    hitboxes: Query<(&Knockback, &GlobalTransform)>,
+    hurtboxes: Query<(&GlobalTransform, Option<&Weight>)>,
+    mut effects: ConsumableEventWriter<ApplyStatusEffect>,
+) {
+    for hit in hits.read() {
+        let (Ok((knockback, hitbox_transform)), Ok((hurtbox_transform, weight))) =
+            (hitboxes.get(hit.hitbox), hurtboxes.get(hit.hurtbox))
+        else {
+            continue;
+        };
+
+        let weight = Weight::of(weight);
+        let direction = (hurtbox_transform.translation() - hitbox_transform.translation())
+            .xy()
+            .try_normalize()
+            // Hit exactly in the centre
+            .unwrap_or(Vec2::X);
+
+        effects.send(ApplyStatusEffect {
+            entity: hit.hurtbox,
+            effect: StatusEffect::knockback(
+                direction * knockback.strength / weight,
+                knockback.duration,
+            ),
+        });
+    }
+}
+
+fn push_characters(
+    collisions: Res<Collisions>,
+    mut characters: Query<(&mut Transform, &CollisionLayers, Option<&Weight>)>,
+) {
+    for contacts in collisions.iter() {
+        let Ok([a, b]) = characters.get_many_mut([contacts.entity1, contacts.entity2]) else {
+            continue;
+        };
+        let ((mut transform_a, layers_a, weight_a), (mut transform_b, layers_b, weight_b)) = (a, b);
+
+        let is_character = |layers: &CollisionLayers| {
+            layers.memberships & CollisionMask::Character != LayerMask::NONE
+        };
+        if !is_character(layers_a) || !is_character(layers_b) {
+            continue;
+        }
+
+        let penetration = contacts
+            .manifolds
+            .iter()
+            .flat_map(|manifold| manifold.contacts.iter())
+            .map(|contact| contact.penetration)
+            .fold(0., f32::max);
+
+        if penetration <= 0. {
+            continue;
+        }
+
+        let direction = (transform_b.translation - transform_a.translation)
+            .xy()
+            .try_normalize()
+            .unwrap_or(Vec2::X);
+
+        let weight_a = Weight::of(weight_a);
+        let weight_b = Weight::of(weight_b);
+        let total_weight = weight_a + weight_b;
+
+        // Lighter one moves more
+        transform_a.translation -= (direction * penetration * weight_b / total_weight).extend(0.);
+        transform_b.translation += (direction * penetration * weight_a / total_weight).extend(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy_consumable_event::{ConsumableEventApp, ConsumableEvents};
+
+    use super::*;
+    use crate::status_effects::StatusEffectKind;
+
+    #[test]
+    fn zero_weight_knockback_is_finite() {
+        let mut app = App::new();
+        app.add_event::<Hit>()
+            .add_consumable_event::<ApplyStatusEffect>();
+
+        let world = app.world_mut();
+        let hitbox = world
+            .spawn((
+                Knockback {
+                    strength: 100.,
+                    duration: Duration::from_secs(1),
+                },
+                GlobalTransform::default(),
+            ))
+            .id();
+        let hurtbox = world
+            .spawn((Weight(0.), GlobalTransform::from_translation(Vec3::X)))
+            .id();
+        world.send_event(Hit { hitbox, hurtbox });
+        world.run_system_once(apply_knockback);
+
+        let mut effects = world.resource_mut::<ConsumableEvents<ApplyStatusEffect>>();
+        let velocity = effects
+            .read()
+            .find_map(|effect| match effect.effect.kind {
+                StatusEffectKind::Knockback(velocity) => Some(velocity),
+                _ => None,
+            })
+            .unwrap();
+        assert!(velocity.is_finite());
+        assert!(velocity.x > 0.);
+    }
+}
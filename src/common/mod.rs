@@ -4,13 +4,29 @@ use animation::Animation;
 use bevy::{ecs::system::EntityCommands, prelude::*};
 
 pub mod animation;
+pub mod aseprite;
 pub mod colliders;
+pub mod frame_animation;
+pub mod hits;
+pub mod reflect_ron;
+pub mod rng;
+pub mod run_on_frame_event;
 pub mod run_on_timer;
+pub mod walls;
+
 pub struct CommonPlugin;
 
 impl Plugin for CommonPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((colliders::CollidersPlugin, animation::AnimationPlugin));
+        app.add_plugins((
+            colliders::CollidersPlugin,
+            animation::AnimationPlugin,
+            frame_animation::FrameAnimationPlugin,
+            aseprite::AsepritePlugin,
+            hits::HitsPlugin,
+            walls::WallsPlugin,
+            rng::RngPlugin,
+        ));
     }
 }
 
@@ -0,0 +1,157 @@
+//! Loads assets from `.ron` files through the type registry,
+//! so the file mirrors the reflected structure of the asset.
+
+use std::{any::TypeId, fmt, marker::PhantomData};
+
+use bevy::{
+    asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    reflect::{
+        serde::TypedReflectDeserializer, GetTypeRegistration, TypeRegistry, TypeRegistryArc,
+    },
+};
+use serde::de::DeserializeSeed;
+
+pub trait ReflectRonAsset: Asset + FromReflect + GetTypeRegistration {
+    /// Extensions of the files, for example `actor.ron`
+    const EXTENSIONS: &'static [&'static str];
+}
+
+pub trait ReflectRonApp {
+    /// Initializes the asset, registers it's type and loader
+    fn init_reflect_ron_asset<A: ReflectRonAsset>(&mut self) -> &mut Self;
+}
+
+impl ReflectRonApp for App {
+    fn init_reflect_ron_asset<A: ReflectRonAsset>(&mut self) -> &mut Self {
+        self.init_asset::<A>()
+            .register_type::<A>()
+            .init_asset_loader::<ReflectRonLoader<A>>()
+    }
+}
+
+pub struct ReflectRonLoader<A: ReflectRonAsset> {
+    type_registry: TypeRegistryArc,
+    _pd: PhantomData<fn() -> A>,
+}
+
+impl<A: ReflectRonAsset> FromWorld for ReflectRonLoader<A> {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+            _pd: PhantomData,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReflectRonLoaderError {
+    Io(std::io::Error),
+    Parse(ron::de::SpannedError),
+    Deserialize(ron::Error),
+    /// Deserialized value doesn't match the asset type
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ReflectRonLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReflectRonLoaderError::Io(err) => write!(f, "Could not read ron file: {err}"),
+            ReflectRonLoaderError::Parse(err) => write!(f, "Could not parse ron file: {err}"),
+            ReflectRonLoaderError::Deserialize(err) => {
+                write!(f, "Could not deserialize ron file: {err}")
+            }
+            ReflectRonLoaderError::Invalid(type_path) => {
+                write!(f, "Ron file doesn't match `{type_path}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReflectRonLoaderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReflectRonLoaderError::Io(err) => Some(err),
+            ReflectRonLoaderError::Parse(err) => Some(err),
+            ReflectRonLoaderError::Deserialize(err) => Some(err),
+            ReflectRonLoaderError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ReflectRonLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        ReflectRonLoaderError::Io(err)
+    }
+}
+
+impl From<ron::de::SpannedError> for ReflectRonLoaderError {
+    fn from(err: ron::de::SpannedError) -> Self {
+        ReflectRonLoaderError::Parse(err)
+    }
+}
+
+impl From<ron::Error> for ReflectRonLoaderError {
+    fn from(err: ron::Error) -> Self {
+        ReflectRonLoaderError::Deserialize(err)
+    }
+}
+
+/// Reads `A` from ron, `A` must be registered in the `registry`
+pub fn deserialize_reflect_ron<A: ReflectRonAsset>(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+) -> Result<A, ReflectRonLoaderError> {
+    let registration = registry
+        .get(TypeId::of::<A>())
+        .expect("asset type is registered by `init_reflect_ron_asset`");
+
+    let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+    let value =
+        TypedReflectDeserializer::new(registration, registry).deserialize(&mut deserializer)?;
+
+    A::from_reflect(&*value).ok_or(ReflectRonLoaderError::Invalid(A::type_path()))
+}
+
+impl<A: ReflectRonAsset> AssetLoader for ReflectRonLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = ReflectRonLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        deserialize_reflect_ron(&bytes, &self.type_registry.read())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        A::EXTENSIONS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{actors::definition::ActorDefinition, tilemap::autotile::Tileset, tuning::Tuning};
+
+    fn load<A: ReflectRonAsset>(path: &str) -> A {
+        let mut registry = TypeRegistry::default();
+        registry.register::<A>();
+
+        let bytes = std::fs::read(format!("assets/{path}")).unwrap();
+        deserialize_reflect_ron(&bytes, &registry).unwrap_or_else(|err| panic!("{path}: {err}"))
+    }
+
+    #[test]
+    fn game_assets_match_their_types() {
+        load::<Tuning>("game.tuning.ron");
+        load::<ActorDefinition>("actors/dasher.actor.ron");
+        load::<Tileset>("textures/ground.tileset.ron");
+    }
+}
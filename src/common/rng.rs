@@ -0,0 +1,144 @@
+//! Seeded randomness shared by the game.
+//!
+//! Each [`RngStream`] is an independent generator derived from the same seed, so drawing more
+//! numbers from one stream doesn't change the others. Streams are restarted only when the seed is
+//! set, so consecutive runs keep drawing new numbers, and the same seed and inputs give the same
+//! runs.
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>();
+    }
+}
+
+/// Systems using randomness get their own stream, so they don't affect each other
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RngStream {
+    /// Decisions of enemies
+    Ai,
+    /// Where and when actors are spawned
+    Spawning,
+    /// [Arena generation](crate::arena_generator)
+    Procgen,
+    /// Effects that don't affect the gameplay
+    Cosmetic,
+}
+
+impl RngStream {
+    const ALL: [RngStream; 4] = [
+        RngStream::Ai,
+        RngStream::Spawning,
+        RngStream::Procgen,
+        RngStream::Cosmetic,
+    ];
+
+    /// Mixed into the seed, so streams don't repeat each other
+    fn salt(self) -> u64 {
+        (self as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+}
+
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: [StdRng; RngStream::ALL.len()],
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: RngStream::ALL.map(|stream| StdRng::seed_from_u64(seed ^ stream.salt())),
+        }
+    }
+
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Sets the seed and restarts all streams
+    #[inline]
+    pub fn set_seed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    #[inline]
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        &mut self.streams[stream as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(7);
+        b.set_seed(42);
+
+        for _ in 0..10 {
+            assert_eq!(
+                a.stream(RngStream::Procgen).gen::<u64>(),
+                b.stream(RngStream::Procgen).gen::<u64>()
+            );
+        }
+    }
+
+    #[test]
+    fn stream_keeps_going_until_seed_is_set() {
+        let mut rng = GameRng::new(42);
+
+        let first: u64 = rng.stream(RngStream::Procgen).gen();
+        let second: u64 = rng.stream(RngStream::Procgen).gen();
+        assert_ne!(first, second);
+
+        rng.set_seed(42);
+        assert_eq!(rng.stream(RngStream::Procgen).gen::<u64>(), first);
+    }
+
+    #[test]
+    fn streams_dont_repeat_each_other() {
+        let mut rng = GameRng::new(42);
+
+        let numbers: Vec<Vec<u64>> = RngStream::ALL
+            .iter()
+            .map(|&stream| (0..10).map(|_| rng.stream(stream).gen()).collect())
+            .collect();
+
+        for (i, a) in numbers.iter().enumerate() {
+            for b in &numbers[i + 1..] {
+                assert!(a.iter().all(|number| !b.contains(number)));
+            }
+        }
+    }
+
+    #[test]
+    fn drawing_from_stream_doesnt_change_others() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+
+        for _ in 0..10 {
+            a.stream(RngStream::Cosmetic).gen::<u64>();
+        }
+
+        for stream in [RngStream::Ai, RngStream::Spawning, RngStream::Procgen] {
+            assert_eq!(a.stream(stream).gen::<u64>(), b.stream(stream).gen::<u64>());
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::{borrow::Cow, marker::PhantomData};
+
+use bevy::{
+    ecs::{
+        component::ComponentHooks,
+        schedule::{InternedScheduleLabel, ScheduleLabel, SystemConfigs},
+    },
+    prelude::*,
+};
+
+use super::frame_animation::{tick_frame_animation, FrameEvent};
+use crate::dynamic_initialization::{
+    DynamicallyInitializedComponentHooks, DynamicallyInitializedSystems, EntitySystem,
+};
+
+/// Runs `T` when [`FrameAnimation`](super::frame_animation::FrameAnimation) of this entity
+/// shows frame with event named `event`.
+pub struct RunOnFrameEvent<T: EntitySystem> {
+    pub event: Cow<'static, str>,
+    _pd: PhantomData<T>,
+}
+
+impl<T: EntitySystem> RunOnFrameEvent<T> {
+    #[inline]
+    pub fn new(event: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            event: event.into(),
+            _pd: default(),
+        }
+    }
+}
+
+impl<T: EntitySystem<In = (), Out = ()>> Component for RunOnFrameEvent<T> {
+    const STORAGE_TYPE: bevy::ecs::component::StorageType =
+        bevy::ecs::component::StorageType::Table;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.dynamically_initialized::<Self>();
+    }
+}
+
+impl<T: EntitySystem<In = (), Out = ()>> DynamicallyInitializedSystems for RunOnFrameEvent<T> {
+    fn systems() -> (InternedScheduleLabel, SystemConfigs) {
+        (
+            Update.intern(),
+            run_on_frame_event::<T>.after(tick_frame_animation),
+        )
+    }
+}
+
+fn run_on_frame_event<T: EntitySystem<In = (), Out = ()>>(
+    mut events: EventReader<FrameEvent>,
+    mut query: Query<(&RunOnFrameEvent<T>, T::Data), T::Filter>,
+    mut param: ParamSet<(T::Param,)>,
+) {
+    for event in events.read() {
+        let Ok((run_on_event, data)) = query.get_mut(event.entity) else {
+            continue;
+        };
+
+        if run_on_event.event == event.name {
+            T::run((), data, param.p0());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{time::TimeUpdateStrategy, utils::Duration};
+
+    use super::*;
+    use crate::{
+        common::frame_animation::{FrameAnimation, FrameAnimationPlugin, FrameClip, FrameClips},
+        dynamic_initialization::{DataItem, DynamicInitializationPlugin, ParamItem},
+    };
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    #[derive(Component, Default)]
+    struct Runs(u32);
+
+    struct CountRuns;
+
+    impl EntitySystem for CountRuns {
+        type Data = &'static mut Runs;
+        type Filter = ();
+        type Param = ();
+
+        type In = ();
+        type Out = ();
+
+        fn run(_: Self::In, mut runs: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+            runs.0 += 1;
+        }
+    }
+
+    #[test]
+    fn frame_event_runs_once_per_pass() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            DynamicInitializationPlugin,
+            FrameAnimationPlugin,
+        ))
+        // Several ticks every frame of the clip
+        .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME / 4));
+
+        let entity = app
+            .world_mut()
+            .spawn((Runs::default(), RunOnFrameEvent::<CountRuns>::new("step")))
+            .id();
+        // Systems of `RunOnFrameEvent` are added before the animation starts
+        app.update();
+
+        let clips = app.world_mut().resource_mut::<Assets<FrameClips>>().add(
+            FrameClips::default().with_clip(
+                "walk",
+                FrameClip::new(true)
+                    .with_frame(0, FRAME)
+                    .with_event_frame(1, FRAME, "step"),
+            ),
+        );
+        app.world_mut().entity_mut(entity).insert((
+            FrameAnimation::new(clips, "walk"),
+            TextureAtlas {
+                layout: Handle::default(),
+                index: 0,
+            },
+        ));
+
+        let mut passes = 0;
+        let mut index = 0;
+        for _ in 0..40 {
+            app.update();
+
+            let new_index = app.world().get::<TextureAtlas>(entity).unwrap().index;
+            if new_index == 1 && index != 1 {
+                passes += 1;
+            }
+            index = new_index;
+        }
+
+        assert!(passes >= 4);
+        assert_eq!(app.world().get::<Runs>(entity).unwrap().0, passes);
+    }
+}
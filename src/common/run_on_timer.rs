@@ -1,10 +1,15 @@
 use std::marker::PhantomData;
 
-use bevy::{ecs::component::ComponentHooks, prelude::*};
+use bevy::{
+    ecs::{
+        component::ComponentHooks,
+        schedule::{InternedScheduleLabel, ScheduleLabel, SystemConfigs},
+    },
+    prelude::*,
+};
 
 use crate::dynamic_initialization::{
     DynamicallyInitializedComponentHooks, DynamicallyInitializedSystems, EntitySystem,
-    ScheduleToAddSystems,
 };
 
 pub struct RunOnTimer<T: EntitySystem> {
@@ -32,11 +37,8 @@ impl<T: EntitySystem<In = (), Out = ()>> Component for RunOnTimer<T> {
 }
 
 impl<T: EntitySystem<In = (), Out = ()>> DynamicallyInitializedSystems for RunOnTimer<T> {
-    fn systems() -> (ScheduleToAddSystems, bevy::ecs::schedule::SystemConfigs) {
-        (
-            ScheduleToAddSystems::Update,
-            tick_run_on_timer::<T>.into_configs(),
-        )
+    fn systems() -> (InternedScheduleLabel, SystemConfigs) {
+        (Update.intern(), tick_run_on_timer::<T>.into_configs())
     }
 }
 
@@ -0,0 +1,123 @@
+//! Solid walls and obstacles.
+//!
+//! Characters are kinematic sensors, so they are moved out of walls here instead of by the solver.
+//! Only the part of the movement going into the wall is removed, so characters slide along it.
+
+use avian2d::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_consumable_event::ConsumableEventWriter;
+
+use super::colliders::CollisionMask;
+use crate::actors::lifecycle::{DespawnActor, Despawning};
+
+pub struct WallsPlugin;
+
+impl Plugin for WallsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            (resolve_walls, stop_projectiles)
+                .after(PhysicsSet::Sync)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct Wall;
+
+/// Projectile is despawned with [`DespawnActor`] when it touches a [`Wall`]
+#[derive(Component)]
+pub struct StoppedByWalls;
+
+pub trait WallCommands {
+    /// Static collider blocking characters and projectiles
+    fn wall(&mut self, collider: Collider) -> &mut Self;
+}
+
+impl WallCommands for EntityCommands<'_> {
+    fn wall(&mut self, collider: Collider) -> &mut Self {
+        self.insert((
+            Wall,
+            RigidBody::Static,
+            collider,
+            CollisionLayers::new(
+                CollisionMask::Wall,
+                [CollisionMask::Character, CollisionMask::Projectile],
+            ),
+        ))
+    }
+}
+
+fn resolve_walls(
+    collisions: Res<Collisions>,
+    walls: Query<&Rotation, With<Wall>>,
+    mut characters: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            &Rotation,
+            &CollisionLayers,
+        ),
+        Without<Wall>,
+    >,
+) {
+    for contacts in collisions.iter() {
+        // Normals point from the first entity to the second
+        let (wall, character, sign) = if walls.contains(contacts.entity1) {
+            (contacts.entity1, contacts.entity2, 1.)
+        } else if walls.contains(contacts.entity2) {
+            (contacts.entity2, contacts.entity1, -1.)
+        } else {
+            continue;
+        };
+
+        let Ok(wall_rotation) = walls.get(wall) else {
+            continue;
+        };
+        let Ok((mut transform, mut velocity, rotation, layers)) = characters.get_mut(character)
+        else {
+            continue;
+        };
+
+        if layers.memberships & CollisionMask::Character == LayerMask::NONE {
+            continue;
+        }
+
+        for manifold in &contacts.manifolds {
+            let first_rotation = if sign > 0. { wall_rotation } else { rotation };
+            // Out of the wall
+            let normal = manifold.global_normal1(first_rotation) * sign;
+
+            let penetration = manifold
+                .contacts
+                .iter()
+                .map(|contact| contact.penetration)
+                .fold(0., f32::max);
+
+            if penetration > 0. {
+                transform.translation += (normal * penetration).extend(0.);
+            }
+
+            let into_wall = velocity.dot(normal);
+            if into_wall < 0. {
+                velocity.0 -= normal * into_wall;
+            }
+        }
+    }
+}
+
+fn stop_projectiles(
+    mut collisions: EventReader<CollisionStarted>,
+    walls: Query<(), With<Wall>>,
+    projectiles: Query<(), (With<StoppedByWalls>, Without<Despawning>)>,
+    mut despawn: ConsumableEventWriter<DespawnActor>,
+) {
+    for CollisionStarted(a, b) in collisions.read() {
+        for (wall, projectile) in [(*a, *b), (*b, *a)] {
+            if walls.contains(wall) && projectiles.contains(projectile) {
+                despawn.send(DespawnActor(projectile));
+            }
+        }
+    }
+}
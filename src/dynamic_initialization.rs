@@ -1,15 +1,22 @@
-use std::any::TypeId;
+use std::{
+    any::TypeId,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 use bevy::{
+    app::{FixedMainScheduleOrder, MainScheduleOrder},
     ecs::{
         component::ComponentHooks,
         query::{QueryData, QueryFilter, WorldQuery},
-        schedule::SystemConfigs,
+        schedule::{InternedScheduleLabel, ScheduleLabel, SystemConfigs},
         system::SystemParam,
         world::DeferredWorld,
     },
     prelude::*,
-    utils::hashbrown::hash_set::Entry,
+    utils::{hashbrown::hash_map::Entry, Duration, Instant},
 };
 use bevy_consumable_event::{ConsumableEventApp, ConsumableEventReader, ConsumableEvents};
 
@@ -17,25 +24,97 @@ pub struct DynamicInitializationPlugin;
 
 impl Plugin for DynamicInitializationPlugin {
     fn build(&self, app: &mut App) {
+        // Systems are added to the schedule right away only if it exists,
+        // so make sure that missing main schedules can only mean they are running
+        let main_schedules = app.world().resource::<MainScheduleOrder>().labels.clone();
+        let fixed_schedules = app
+            .world()
+            .resource::<FixedMainScheduleOrder>()
+            .labels
+            .clone();
+        for label in main_schedules.into_iter().chain(fixed_schedules) {
+            app.init_schedule(label);
+        }
+
         app.init_resource::<DynamicInitializationRegistry>()
-            .add_consumable_event::<AddSystemsToUpdate>()
-            .add_consumable_event::<AddSystemsToFixedUpdate>()
-            .add_systems(
-                Last,
-                (
-                    add_dynamic_systems_to_fixed_update,
-                    add_dynamic_systems_to_update,
-                ),
-            );
+            .add_persistent_consumable_event::<AddSystemsTo>()
+            // Running schedules are removed from `Schedules`, so systems for them are added
+            // at the first schedule that runs after them
+            .add_systems(First, add_dynamic_systems)
+            .add_systems(PreUpdate, add_dynamic_systems)
+            .add_systems(FixedFirst, add_dynamic_systems)
+            .add_systems(FixedLast, add_dynamic_systems)
+            .add_systems(PostUpdate, add_dynamic_systems)
+            .add_systems(Last, (add_all_dynamic_systems, flush_run_timings));
     }
 }
 
-type TypeIdSet = bevy::utils::hashbrown::HashSet<TypeId, bevy::utils::NoOpHash>;
+type TypeIdMap<V> = bevy::utils::hashbrown::HashMap<TypeId, V, bevy::utils::NoOpHash>;
 
 /// Used to store, which types were dynamically initialized to the world. Usually used in generic contxets.
 #[derive(Default, Resource)]
 pub struct DynamicInitializationRegistry {
-    pub registry: TypeIdSet,
+    pub registry: TypeIdMap<DynamicallyInitializedInfo>,
+}
+
+/// Debug information about dynamically initialized type
+pub struct DynamicallyInitializedInfo {
+    pub type_name: &'static str,
+    /// Set for [`DynamicallyInitializedSystems`]
+    pub schedule: Option<InternedScheduleLabel>,
+    /// Time from the start of the first system to the end of the last one in the last run,
+    /// updated in [`Last`]. Includes time the systems wait for the ones they are ordered after
+    pub last_run: Duration,
+    /// Exponential moving average of [`last_run`](Self::last_run)
+    pub average_run: Duration,
+    /// Set for [`DynamicallyInitializedSystems`]
+    timing: Option<Arc<RunTiming>>,
+}
+
+/// Written by the systems around [`DynamicallyInitializedSystems`], so they don't need
+/// [`DynamicInitializationRegistry`] and can run in parallel with other systems
+struct RunTiming {
+    epoch: Instant,
+    /// Nanoseconds since `epoch`
+    started: AtomicU64,
+    /// Nanoseconds, [`RunTiming::NOT_FINISHED`] until the run is finished
+    last_run: AtomicU64,
+}
+
+impl RunTiming {
+    /// Last run was already flushed to [`DynamicallyInitializedInfo`]
+    const NOT_FINISHED: u64 = u64::MAX;
+
+    fn new() -> Self {
+        Self {
+            epoch: Instant::now(),
+            started: AtomicU64::new(0),
+            last_run: AtomicU64::new(Self::NOT_FINISHED),
+        }
+    }
+
+    #[inline]
+    fn now(&self) -> u64 {
+        self.epoch.elapsed().as_nanos() as u64
+    }
+
+    fn begin(&self) {
+        self.started.store(self.now(), Ordering::Relaxed);
+    }
+
+    fn end(&self) {
+        let started = self.started.load(Ordering::Relaxed);
+        self.last_run
+            .store(self.now().saturating_sub(started), Ordering::Relaxed);
+    }
+
+    /// Duration of the run finished since the last call
+    fn take(&self) -> Option<Duration> {
+        match self.last_run.swap(Self::NOT_FINISHED, Ordering::Relaxed) {
+            Self::NOT_FINISHED => None,
+            nanos => Some(Duration::from_nanos(nanos)),
+        }
+    }
 }
 
 impl DynamicInitializationRegistry {
@@ -45,7 +124,13 @@ impl DynamicInitializationRegistry {
         match registry.registry.entry(TypeId::of::<T>()) {
             Entry::Occupied(_) => (),
             Entry::Vacant(vacant) => {
-                vacant.insert();
+                vacant.insert(DynamicallyInitializedInfo {
+                    type_name: std::any::type_name::<T>(),
+                    schedule: None,
+                    last_run: Duration::ZERO,
+                    average_run: Duration::ZERO,
+                    timing: None,
+                });
 
                 T::initialize(world);
             }
@@ -53,29 +138,78 @@ impl DynamicInitializationRegistry {
     }
 }
 
-// For jam hadcoded label is good enough
+/// Moves timings of the runs finished this frame to the registry.
+/// Schedules running several times a frame report only the last run
+fn flush_run_timings(mut registry: ResMut<DynamicInitializationRegistry>) {
+    for info in registry.registry.values_mut() {
+        let Some(last_run) = info.timing.as_ref().and_then(|timing| timing.take()) else {
+            continue;
+        };
+
+        info.last_run = last_run;
+        info.average_run = info.average_run.mul_f32(0.9) + last_run.mul_f32(0.1);
+    }
+}
+
+/// Adds `systems` to the `schedule`, creating the schedule if it doesn't exist.
+///
+/// Systems are guaranteed to be added before the next run of the `schedule`,
+/// unless `schedule` is [`Main`], runs other schedules itself or is run from [`Last`].
+///
+/// `systems` may be put in sets, e.g. [`ActionSet`](crate::action_behaviour::ActionSet),
+/// as long as these sets are configured in the `schedule`, and ordered relative to existing systems
+/// with `before`/`after`.
 #[derive(Event)]
-pub struct AddSystemsToUpdate(pub SystemConfigs);
+pub struct AddSystemsTo {
+    pub schedule: InternedScheduleLabel,
+    pub systems: SystemConfigs,
+}
 
-fn add_dynamic_systems_to_update(
-    mut to_add: ConsumableEventReader<AddSystemsToUpdate>,
+impl AddSystemsTo {
+    #[inline]
+    pub fn new<M>(schedule: impl ScheduleLabel, systems: impl IntoSystemConfigs<M>) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            systems: systems.into_configs(),
+        }
+    }
+}
+
+/// Adds systems only to the schedules that are not running right now
+fn add_dynamic_systems(
+    mut to_add: ConsumableEventReader<AddSystemsTo>,
     mut schedules: ResMut<Schedules>,
 ) {
-    for AddSystemsToUpdate(systems) in to_add.read_and_consume_all() {
-        schedules.add_systems(Update, systems);
+    for event in to_add.read() {
+        if schedules.contains(event.schedule) {
+            let AddSystemsTo { schedule, systems } = event.consume();
+            schedules.add_systems(schedule, systems);
+        }
     }
 }
 
-// For jam hadcoded label is good enough
-#[derive(Event)]
-pub struct AddSystemsToFixedUpdate(pub SystemConfigs);
-
-fn add_dynamic_systems_to_fixed_update(
-    mut to_add: ConsumableEventReader<AddSystemsToFixedUpdate>,
+/// At the end of the frame only [`Main`] and [`Last`] are running,
+/// so other schedules that are still missing don't exist yet and are created.
+///
+/// [`Last`] is put back into [`Schedules`] after this system, replacing anything added to it now,
+/// so systems for it are left to [`First`] of the next frame. Schedules run by systems in [`Last`]
+/// have the same problem and aren't supported.
+fn add_all_dynamic_systems(
+    mut to_add: ConsumableEventReader<AddSystemsTo>,
     mut schedules: ResMut<Schedules>,
 ) {
-    for AddSystemsToFixedUpdate(systems) in to_add.read_and_consume_all() {
-        schedules.add_systems(FixedUpdate, systems);
+    let last = Last.intern();
+
+    for event in to_add.read() {
+        debug_assert!(
+            event.schedule != Main.intern(),
+            "systems can't be added to `Main` dynamically"
+        );
+
+        if event.schedule != last {
+            let AddSystemsTo { schedule, systems } = event.consume();
+            schedules.add_systems(schedule, systems);
+        }
     }
 }
 
@@ -83,33 +217,38 @@ fn add_dynamic_systems_to_fixed_update(
 ///
 /// Usual use case is to implement it for the component and set it's `on_add` hook to register it into the registry.
 ///
-/// To add systems to the world, use [`AddSystemsToUpdate`] or similar events.
+/// To add systems to the world, use [`AddSystemsTo`] event.
 pub trait DynamicallyInitialized: Send + Sync + 'static {
     fn initialize(world: DeferredWorld);
 }
 
-pub enum ScheduleToAddSystems {
-    Update,
-    FixedUpdate,
-}
-
 pub trait DynamicallyInitializedSystems: DynamicallyInitialized {
-    fn systems() -> (ScheduleToAddSystems, SystemConfigs);
+    /// Schedule to add systems to, any schedule including `OnEnter(..)` will do
+    fn systems() -> (InternedScheduleLabel, SystemConfigs);
 }
 
 impl<T: DynamicallyInitializedSystems> DynamicallyInitialized for T {
     fn initialize(mut world: DeferredWorld) {
         let (schedule, systems) = T::systems();
 
-        match schedule {
-            ScheduleToAddSystems::Update => {
-                let mut events = world.resource_mut::<ConsumableEvents<AddSystemsToUpdate>>();
-                events.send(AddSystemsToUpdate(systems));
-            }
-            ScheduleToAddSystems::FixedUpdate => {
-                let mut events = world.resource_mut::<ConsumableEvents<AddSystemsToFixedUpdate>>();
-                events.send(AddSystemsToFixedUpdate(systems));
-            }
+        let timing = Arc::new(RunTiming::new());
+        let mut registry = world.resource_mut::<DynamicInitializationRegistry>();
+        if let Some(info) = registry.registry.get_mut(&TypeId::of::<T>()) {
+            info.schedule = Some(schedule);
+            info.timing = Some(timing.clone());
+        }
+
+        let begin_timing = timing.clone();
+        let systems = (move || begin_timing.begin(), systems, move || timing.end()).chain();
+
+        // Adding right away lets entities spawned before the schedule runs be processed this frame.
+        // Running schedules are missing from `Schedules`, systems for them are added later.
+        let mut schedules = world.resource_mut::<Schedules>();
+        if let Some(schedule) = schedules.get_mut(schedule) {
+            schedule.add_systems(systems);
+        } else {
+            let mut events = world.resource_mut::<ConsumableEvents<AddSystemsTo>>();
+            events.send(AddSystemsTo { schedule, systems });
         }
     }
 }
@@ -158,3 +297,235 @@ impl EntitySystem for () {
     ) -> Self::Out {
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::{component::StorageType, schedule::ScheduleLabel},
+        time::TimeUpdateStrategy,
+    };
+
+    use super::*;
+    use crate::common::{animation::Animation, run_on_timer::RunOnTimer};
+
+    /// Number of times the systems of the entity ran
+    #[derive(Component, Default)]
+    struct Runs(u32);
+
+    struct CountRuns;
+
+    impl EntitySystem for CountRuns {
+        type Data = &'static mut Runs;
+        type Filter = ();
+        type Param = ();
+
+        type In = f32;
+        type Out = ();
+
+        fn run(_: Self::In, mut runs: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+            runs.0 += 1;
+        }
+    }
+
+    /// Counts [`Runs`] in [`FixedUpdate`]
+    struct FixedRuns;
+
+    impl Component for FixedRuns {
+        const STORAGE_TYPE: StorageType = StorageType::Table;
+
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks.dynamically_initialized::<Self>();
+        }
+    }
+
+    impl DynamicallyInitializedSystems for FixedRuns {
+        fn systems() -> (InternedScheduleLabel, SystemConfigs) {
+            (
+                FixedUpdate.intern(),
+                (|mut query: Query<&mut Runs, With<FixedRuns>>| {
+                    for mut runs in query.iter_mut() {
+                        runs.0 += 1;
+                    }
+                })
+                .into_configs(),
+            )
+        }
+    }
+
+    /// Counts [`Runs`] in [`Last`]
+    struct LastRuns;
+
+    impl Component for LastRuns {
+        const STORAGE_TYPE: StorageType = StorageType::Table;
+
+        fn register_component_hooks(hooks: &mut ComponentHooks) {
+            hooks.dynamically_initialized::<Self>();
+        }
+    }
+
+    impl DynamicallyInitializedSystems for LastRuns {
+        fn systems() -> (InternedScheduleLabel, SystemConfigs) {
+            (
+                Last.intern(),
+                (|mut query: Query<&mut Runs, With<LastRuns>>| {
+                    for mut runs in query.iter_mut() {
+                        runs.0 += 1;
+                    }
+                })
+                .into_configs(),
+            )
+        }
+    }
+
+    #[derive(Resource)]
+    struct SpawnNext(bool);
+
+    /// App spawning `bundle` once from `schedule` after [`SpawnNext`] is set
+    fn app<B: Bundle>(schedule: impl ScheduleLabel, bundle: fn() -> B) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, DynamicInitializationPlugin))
+            // Several fixed steps every frame
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .insert_resource(SpawnNext(false))
+            .add_systems(
+                schedule,
+                move |mut commands: Commands, mut spawn: ResMut<SpawnNext>| {
+                    if std::mem::take(&mut spawn.0) {
+                        commands.spawn(bundle());
+                    }
+                },
+            );
+
+        // First frame has no delta time and runs no fixed steps
+        app.update();
+        app
+    }
+
+    /// Spawns the entity in the next frame and returns it's [`Runs`] after that frame
+    fn runs_in_spawn_frame(app: &mut App) -> u32 {
+        app.world_mut().resource_mut::<SpawnNext>().0 = true;
+        app.update();
+
+        let mut query = app.world_mut().query::<&Runs>();
+        query.single(app.world()).0
+    }
+
+    fn animation() -> impl Bundle {
+        (
+            Runs::default(),
+            Animation::<CountRuns, ()>::new(Timer::from_seconds(10., TimerMode::Once)),
+        )
+    }
+
+    fn run_on_timer() -> impl Bundle {
+        RunOnTimer::<()>::new(Timer::from_seconds(10., TimerMode::Once))
+    }
+
+    fn fixed_runs() -> impl Bundle {
+        (Runs::default(), FixedRuns)
+    }
+
+    fn last_runs() -> impl Bundle {
+        (Runs::default(), LastRuns)
+    }
+
+    fn run_on_timer_elapsed(app: &mut App) -> Duration {
+        let mut query = app.world_mut().query::<&RunOnTimer<()>>();
+        query.single(app.world()).timer.elapsed()
+    }
+
+    #[test]
+    fn animation_spawned_in_fixed_update_ticks_in_same_frame() {
+        let mut app = app(FixedUpdate, animation);
+
+        assert_eq!(runs_in_spawn_frame(&mut app), 1);
+    }
+
+    #[test]
+    fn animation_spawned_in_update_ticks_in_next_update() {
+        let mut app = app(Update, animation);
+
+        assert_eq!(runs_in_spawn_frame(&mut app), 0);
+        app.update();
+
+        let mut query = app.world_mut().query::<&Runs>();
+        assert_eq!(query.single(app.world()).0, 1);
+    }
+
+    #[test]
+    fn run_on_timer_spawned_in_fixed_update_ticks_in_same_frame() {
+        let mut app = app(FixedUpdate, run_on_timer);
+
+        app.world_mut().resource_mut::<SpawnNext>().0 = true;
+        app.update();
+
+        assert_eq!(run_on_timer_elapsed(&mut app), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn run_on_timer_spawned_in_update_ticks_in_next_update() {
+        let mut app = app(Update, run_on_timer);
+
+        app.world_mut().resource_mut::<SpawnNext>().0 = true;
+        app.update();
+        assert_eq!(run_on_timer_elapsed(&mut app), Duration::ZERO);
+
+        app.update();
+        assert_eq!(run_on_timer_elapsed(&mut app), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn fixed_update_system_spawned_in_update_runs_in_next_fixed_step() {
+        let mut app = app(Update, fixed_runs);
+
+        assert_eq!(runs_in_spawn_frame(&mut app), 0);
+        app.update();
+
+        let mut query = app.world_mut().query::<&Runs>();
+        assert!(query.single(app.world()).0 > 0);
+    }
+
+    #[test]
+    fn run_timings_are_flushed_to_registry() {
+        let mut app = app(Update, animation);
+        runs_in_spawn_frame(&mut app);
+        app.update();
+
+        let registry = app.world().resource::<DynamicInitializationRegistry>();
+        let info = &registry.registry[&TypeId::of::<Animation<CountRuns, ()>>()];
+        assert_eq!(info.schedule, Some(Update.intern()));
+        assert!(info.last_run > Duration::ZERO);
+        assert!(info.average_run > Duration::ZERO);
+    }
+
+    #[test]
+    fn fixed_update_system_spawned_in_fixed_update_runs_in_next_fixed_step() {
+        let mut app = app(FixedUpdate, fixed_runs);
+
+        // Spawned in the first fixed step of the frame, the rest of them run the system
+        assert!(runs_in_spawn_frame(&mut app) > 0);
+    }
+
+    #[test]
+    fn last_system_requested_in_last_runs_in_next_frame() {
+        let mut app = app(Update, last_runs);
+        // Requests the systems while `Last` is running
+        app.add_systems(
+            Last,
+            (|mut commands: Commands| {
+                commands.spawn(last_runs());
+            })
+            .run_if(run_once())
+            .before(add_all_dynamic_systems),
+        );
+
+        app.update();
+        let mut query = app.world_mut().query::<&Runs>();
+        assert_eq!(query.single(app.world()).0, 0);
+
+        app.update();
+        assert_eq!(query.single(app.world()).0, 1);
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt::Write;
+
+use bevy::{prelude::*, utils::get_short_name};
+
+use crate::dynamic_initialization::DynamicInitializationRegistry;
+
+/// Names of the types registered with
+/// [`register_actor`](crate::actors::AppRegisteringActors::register_actor),
+/// [`register_action`](crate::action_behaviour::ActionBehaviourApp::register_action) and
+/// [`register_behaviour`](crate::action_behaviour::ActionBehaviourApp::register_behaviour)
+#[derive(Resource, Default)]
+pub struct RegisteredTypes {
+    pub actors: Vec<&'static str>,
+    pub actions: Vec<&'static str>,
+    pub behaviours: Vec<&'static str>,
+}
+
+impl RegisteredTypes {
+    #[inline]
+    pub fn get(app: &mut App) -> Mut<'_, RegisteredTypes> {
+        app.world_mut()
+            .get_resource_or_insert_with(RegisteredTypes::default)
+    }
+}
+
+const TOGGLE_OVERLAY_KEY: KeyCode = KeyCode::F3;
+const DUMP_TO_CONSOLE_KEY: KeyCode = KeyCode::F4;
+
+/// Lists registered types and dynamically initialized systems with their run timings.
+/// Toggled with F3, F4 prints the same list to the console.
+pub struct DebugOverlayPlugin;
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RegisteredTypes>()
+            .add_systems(Update, (toggle_overlay, update_overlay, dump_to_console));
+    }
+}
+
+#[derive(Component)]
+struct DebugOverlay;
+
+fn toggle_overlay(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    overlay: Query<Entity, With<DebugOverlay>>,
+) {
+    if !keyboard_input.just_pressed(TOGGLE_OVERLAY_KEY) {
+        return;
+    }
+
+    if let Ok(entity) = overlay.get_single() {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 14.0,
+                color: Color::linear_rgb(0.9, 0.9, 0.9),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(5.0),
+            left: Val::Px(5.0),
+            ..default()
+        })
+        .with_background_color(Color::linear_rgba(0., 0., 0., 0.7)),
+        DebugOverlay,
+    ));
+}
+
+fn update_overlay(
+    registered: Res<RegisteredTypes>,
+    registry: Res<DynamicInitializationRegistry>,
+    mut overlay: Query<&mut Text, With<DebugOverlay>>,
+) {
+    for mut text in overlay.iter_mut() {
+        text.sections[0].value = describe(&registered, &registry);
+    }
+}
+
+fn dump_to_console(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    registered: Res<RegisteredTypes>,
+    registry: Res<DynamicInitializationRegistry>,
+) {
+    if keyboard_input.just_pressed(DUMP_TO_CONSOLE_KEY) {
+        info!("\n{}", describe(&registered, &registry));
+    }
+}
+
+fn describe(registered: &RegisteredTypes, registry: &DynamicInitializationRegistry) -> String {
+    let mut out = String::new();
+
+    for (title, names) in [
+        ("Actors", &registered.actors),
+        ("Actions", &registered.actions),
+        ("Behaviours", &registered.behaviours),
+    ] {
+        let _ = writeln!(out, "{title}:");
+        for name in names {
+            let _ = writeln!(out, "  {}", get_short_name(name));
+        }
+    }
+
+    let _ = writeln!(out, "Dynamically initialized:");
+
+    let mut infos: Vec<_> = registry.registry.values().collect();
+    infos.sort_by_key(|info| info.type_name);
+
+    for info in infos {
+        let _ = write!(out, "  {}", get_short_name(info.type_name));
+
+        if let Some(schedule) = info.schedule {
+            let _ = write!(
+                out,
+                " [{:?}] {:.3} ms (avg {:.3} ms)",
+                schedule,
+                info.last_run.as_secs_f64() * 1000.,
+                info.average_run.as_secs_f64() * 1000.,
+            );
+        }
+
+        let _ = writeln!(out);
+    }
+
+    out
+}
@@ -2,11 +2,18 @@
 
 pub mod action_behaviour;
 pub mod actors;
+pub mod arena_generator;
+pub mod camera;
 pub mod common;
 pub mod dynamic_initialization;
 pub mod input_map;
+pub mod introspection;
 pub mod menu_state;
+pub mod navigation;
 pub mod playing_state;
+pub mod status_effects;
+pub mod tilemap;
+pub mod tuning;
 pub mod utils;
 
 use crate::{
@@ -16,10 +23,16 @@ use crate::{
 use avian2d::prelude::*;
 // #[cfg(debug_assertions)]
 // use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use arena_generator::ArenaGeneratorPlugin;
 use bevy::prelude::*;
+use camera::CameraPlugin;
 use common::CommonPlugin;
 use dynamic_initialization::DynamicInitializationPlugin;
+use navigation::NavigationPlugin;
 use playing_state::PlayingPlugin;
+use status_effects::StatusEffectsPlugin;
+use tilemap::TilemapPlugin;
+use tuning::TuningPlugin;
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
 enum GameState {
@@ -37,18 +50,26 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<GameState>().add_plugins((
             PhysicsPlugins::default(),
+            // Actors use common assets and components, so they are registered after it
+            DynamicInitializationPlugin,
+            CommonPlugin,
+            TuningPlugin,
+            CameraPlugin,
             MenuPlugin,
             PlayingPlugin,
+            ArenaGeneratorPlugin,
+            TilemapPlugin,
+            NavigationPlugin,
             InputMapPlugin,
             ActionBehaviourPlugin,
+            StatusEffectsPlugin,
             RegisterActors,
-            DynamicInitializationPlugin,
-            CommonPlugin,
         ));
 
         #[cfg(debug_assertions)]
         {
             //app.add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+            app.add_plugins(introspection::DebugOverlayPlugin);
         }
     }
 }
@@ -3,7 +3,6 @@
 
 use bevy::asset::AssetMetaCheck;
 use bevy::prelude::*;
-use bevy::render::camera::ScalingMode;
 use bevy::window::PrimaryWindow;
 use bevy::winit::WinitWindows;
 use bevy::DefaultPlugins;
@@ -40,7 +39,7 @@ fn main() {
                 .set(ImagePlugin::default_nearest()),
         )
         .add_plugins(GamePlugin)
-        .add_systems(Startup, (set_window_icon, setup_camera))
+        .add_systems(Startup, set_window_icon)
         .run();
 }
 
@@ -64,15 +63,3 @@ fn set_window_icon(
         primary.set_window_icon(Some(icon));
     };
 }
-
-fn setup_camera(mut commands: Commands) {
-    commands.spawn(Camera2dBundle {
-        projection: OrthographicProjection {
-            far: 1000.,
-            near: -1000.,
-            scaling_mode: ScalingMode::WindowSize(4.),
-            ..default()
-        },
-        ..default()
-    });
-}
@@ -1,14 +1,23 @@
-use crate::GameState;
-use bevy::prelude::*;
+use crate::{arena_generator::EndlessMode, common::rng::GameRng, GameState};
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
 
 pub struct MenuPlugin;
 
-/// This plugin is responsible for the game menu (containing only one button...)
+/// This plugin is responsible for the game menu (buttons starting a normal or an endless run)
 /// The menu is only drawn during the State `GameState::Menu` and is removed when that state is exited
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Menu), setup_menu)
-            .add_systems(Update, click_play_button.run_if(in_state(GameState::Menu)))
+            .add_systems(
+                Update,
+                (click_play_button, enter_seed).run_if(in_state(GameState::Menu)),
+            )
             .add_systems(OnExit(GameState::Menu), cleanup_menu);
     }
 }
@@ -31,7 +40,13 @@ impl Default for ButtonColors {
 #[derive(Component)]
 struct Menu;
 
-fn setup_menu(mut commands: Commands) {
+/// Shows the seed of the run, typing digits changes it
+#[derive(Component)]
+struct SeedText;
+
+const MAX_SEED_DIGITS: usize = 20;
+
+fn setup_menu(mut commands: Commands, rng: Res<GameRng>) {
     info!("menu");
     commands
         .spawn((
@@ -49,54 +64,94 @@ fn setup_menu(mut commands: Commands) {
             Menu,
         ))
         .with_children(|children| {
-            let button_colors = ButtonColors::default();
-            children
-                .spawn((
-                    ButtonBundle {
-                        style: Style {
-                            width: Val::Px(140.0),
-                            height: Val::Px(50.0),
-                            justify_content: JustifyContent::Center,
-                            align_items: AlignItems::Center,
-                            ..Default::default()
-                        },
-                        background_color: button_colors.normal.into(),
-                        ..Default::default()
+            spawn_button(children, "Play", false);
+            spawn_button(children, "Endless", true);
+
+            children.spawn((
+                TextBundle::from_section(
+                    seed_text(rng.seed()),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::linear_rgb(0.9, 0.9, 0.9),
+                        ..default()
                     },
-                    button_colors,
-                    ChangeState(GameState::Playing),
-                ))
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "Play",
-                        TextStyle {
-                            font_size: 40.0,
-                            color: Color::linear_rgb(0.9, 0.9, 0.9),
-                            ..default()
-                        },
-                    ));
-                });
+                )
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(15.0)),
+                    ..default()
+                }),
+                SeedText,
+            ));
+            children.spawn(TextBundle::from_section(
+                "Type digits to change the seed",
+                TextStyle {
+                    font_size: 16.0,
+                    color: Color::linear_rgb(0.6, 0.6, 0.6),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn spawn_button(children: &mut ChildBuilder, text: &str, endless: bool) {
+    let button_colors = ButtonColors::default();
+    children
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(200.0),
+                    height: Val::Px(50.0),
+                    margin: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: button_colors.normal.into(),
+                ..Default::default()
+            },
+            button_colors,
+            ChangeState(GameState::Playing),
+            StartEndless(endless),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::linear_rgb(0.9, 0.9, 0.9),
+                    ..default()
+                },
+            ));
         });
 }
 
 #[derive(Component)]
 struct ChangeState(GameState);
 
+/// Whether the run is played in the [`EndlessMode`]
+#[derive(Component)]
+struct StartEndless(bool);
+
 fn click_play_button(
     mut next_state: ResMut<NextState<GameState>>,
+    mut endless_mode: ResMut<EndlessMode>,
     mut interaction_query: Query<
         (
             &Interaction,
             &mut BackgroundColor,
             &ButtonColors,
             Option<&ChangeState>,
+            Option<&StartEndless>,
         ),
         (Changed<Interaction>, With<Button>),
     >,
 ) {
-    for (interaction, mut color, button_colors, change_state) in &mut interaction_query {
+    for (interaction, mut color, button_colors, change_state, endless) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => {
+                if let Some(StartEndless(endless)) = endless {
+                    endless_mode.enabled = *endless;
+                }
                 if let Some(state) = change_state {
                     next_state.set(state.0.clone());
                 }
@@ -111,6 +166,55 @@ fn click_play_button(
     }
 }
 
+fn seed_text(seed: u64) -> String {
+    format!("Seed: {seed}")
+}
+
+fn enter_seed(
+    mut keys: EventReader<KeyboardInput>,
+    mut rng: ResMut<GameRng>,
+    mut text: Query<&mut Text, With<SeedText>>,
+) {
+    let mut digits = rng.seed().to_string();
+
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &key.logical_key {
+            Key::Backspace => {
+                digits.pop();
+            }
+            Key::Character(character)
+                if character.chars().all(|c| c.is_ascii_digit())
+                    && digits.len() < MAX_SEED_DIGITS =>
+            {
+                // Typing into a zero seed replaces it
+                if digits == "0" {
+                    digits.clear();
+                }
+                digits.push_str(character);
+            }
+            _ => continue,
+        }
+
+        // Digits beyond u64 are ignored
+        if let Ok(seed) = digits.parse() {
+            rng.set_seed(seed);
+        } else if digits.is_empty() {
+            rng.set_seed(0);
+        }
+        digits = rng.seed().to_string();
+    }
+
+    if rng.is_changed() {
+        for mut text in &mut text {
+            text.sections[0].value = seed_text(rng.seed());
+        }
+    }
+}
+
 fn cleanup_menu(mut commands: Commands, menu: Query<Entity, With<Menu>>) {
     for entity in menu.iter() {
         commands.entity(entity).despawn_recursive();
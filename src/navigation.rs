@@ -0,0 +1,328 @@
+//! Navigation of enemies over [`LandTiles`].
+//!
+//! [`NavGrid`] mirrors which cells can be walked on and is updated from [`TileChanged`] events.
+//! Tiles that are fading away or showing up are blocked until their animation ends.
+//! A flow field towards the player is rebuilt only when the grid or the player's cell changes,
+//! so any number of enemies can ask for the next waypoint for free.
+//! Paths between arbitrary points are found with A*.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    actors::{lifecycle::ActorOf, player::Player},
+    playing_state::{LandTiles, LayoutSetup, TileChanged},
+    tilemap::{TileFadeAwayAnimation, TileShowUpAnimation, TileVisual},
+    GameState,
+};
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                reset_nav_grid.after(LayoutSetup),
+            )
+            .add_systems(
+                Update,
+                (update_nav_grid, block_animating_tiles, update_flow_field)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+const ORTHOGONAL_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), ORTHOGONAL_COST),
+    (IVec2::new(-1, 0), ORTHOGONAL_COST),
+    (IVec2::new(0, 1), ORTHOGONAL_COST),
+    (IVec2::new(0, -1), ORTHOGONAL_COST),
+    (IVec2::new(1, 1), DIAGONAL_COST),
+    (IVec2::new(1, -1), DIAGONAL_COST),
+    (IVec2::new(-1, 1), DIAGONAL_COST),
+    (IVec2::new(-1, -1), DIAGONAL_COST),
+];
+
+/// Walkable cells of [`LandTiles`] and the flow field towards the player
+#[derive(Resource)]
+pub struct NavGrid {
+    /// Same as the size of [`LandTiles`]
+    size: UVec2,
+    alive: Vec<bool>,
+    /// Cells marked impassable by other systems, for example tiles fading away or showing up
+    blocked: Vec<bool>,
+    /// Cost of reaching `target` from each cell
+    flow: Vec<Option<u32>>,
+    target: Option<IVec2>,
+    dirty: bool,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self::new(UVec2::ZERO)
+    }
+}
+
+impl NavGrid {
+    fn new(size: UVec2) -> Self {
+        let len = (size.x * size.y) as usize;
+
+        Self {
+            size,
+            alive: vec![true; len],
+            blocked: vec![false; len],
+            flow: vec![None; len],
+            target: None,
+            dirty: true,
+        }
+    }
+
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.size.x as i32 || cell.y >= self.size.y as i32
+        {
+            None
+        } else {
+            Some(cell.x as usize * self.size.y as usize + cell.y as usize)
+        }
+    }
+
+    /// Cell is inside the grid, alive and not blocked
+    pub fn is_passable(&self, cell: IVec2) -> bool {
+        self.index(cell)
+            .is_some_and(|index| self.alive[index] && !self.blocked[index])
+    }
+
+    /// Makes cell impassable even if the tile is alive
+    pub fn block(&mut self, cell: IVec2) {
+        self.set_blocked(cell, true);
+    }
+
+    pub fn unblock(&mut self, cell: IVec2) {
+        self.set_blocked(cell, false);
+    }
+
+    fn set_blocked(&mut self, cell: IVec2, blocked: bool) {
+        if let Some(index) = self.index(cell) {
+            if self.blocked[index] != blocked {
+                self.blocked[index] = blocked;
+                self.dirty = true;
+            }
+        }
+    }
+
+    fn set_alive(&mut self, cell: IVec2, alive: bool) {
+        if let Some(index) = self.index(cell) {
+            if self.alive[index] != alive {
+                self.alive[index] = alive;
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Passable neighbours with the cost of moving to them.
+    /// Diagonal moves don't cut corners of impassable cells
+    fn neighbours(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        NEIGHBOURS.iter().filter_map(move |&(offset, cost)| {
+            let neighbour = cell + offset;
+            let corners_passable = offset.x == 0
+                || offset.y == 0
+                || (self.is_passable(cell + IVec2::new(offset.x, 0))
+                    && self.is_passable(cell + IVec2::new(0, offset.y)));
+
+            (corners_passable && self.is_passable(neighbour)).then_some((neighbour, cost))
+        })
+    }
+
+    /// Cost of reaching the player from the cell, `None` if player can't be reached
+    pub fn flow_cost(&self, cell: IVec2) -> Option<u32> {
+        self.index(cell).and_then(|index| self.flow[index])
+    }
+
+    /// Neighbour of the cell closer to the player
+    pub fn next_cell_to_player(&self, cell: IVec2) -> Option<IVec2> {
+        let cost = self.flow_cost(cell)?;
+
+        self.neighbours(cell)
+            .filter_map(|(neighbour, _)| Some((neighbour, self.flow_cost(neighbour)?)))
+            .filter(|&(_, neighbour_cost)| neighbour_cost < cost)
+            .min_by_key(|&(_, neighbour_cost)| neighbour_cost)
+            .map(|(neighbour, _)| neighbour)
+    }
+
+    fn rebuild_flow(&mut self) {
+        self.flow.fill(None);
+        self.dirty = false;
+
+        let Some(target) = self.target.filter(|&target| self.is_passable(target)) else {
+            return;
+        };
+
+        let mut frontier = BinaryHeap::new();
+        let target_index = self.index(target).unwrap();
+        self.flow[target_index] = Some(0);
+        frontier.push(Reverse((0, target.x, target.y)));
+
+        while let Some(Reverse((cost, x, y))) = frontier.pop() {
+            let cell = IVec2::new(x, y);
+            if self.flow_cost(cell).is_some_and(|best| best < cost) {
+                continue;
+            }
+
+            let neighbours: Vec<_> = self.neighbours(cell).collect();
+            for (neighbour, step) in neighbours {
+                let index = self.index(neighbour).unwrap();
+                let new_cost = cost + step;
+                if self.flow[index].is_none_or(|old| new_cost < old) {
+                    self.flow[index] = Some(new_cost);
+                    frontier.push(Reverse((new_cost, neighbour.x, neighbour.y)));
+                }
+            }
+        }
+    }
+
+    /// Cells from `start` to `goal` including both, `None` if there is no path
+    pub fn find_path(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        if !self.is_passable(start) || !self.is_passable(goal) {
+            return None;
+        }
+
+        // Octile distance
+        let heuristic = |cell: IVec2| {
+            let delta = (goal - cell).abs();
+            let (min, max) = (delta.min_element() as u32, delta.max_element() as u32);
+            DIAGONAL_COST * min + ORTHOGONAL_COST * (max - min)
+        };
+
+        let len = self.alive.len();
+        let mut costs = vec![u32::MAX; len];
+        let mut came_from = vec![None; len];
+        let mut frontier = BinaryHeap::new();
+
+        costs[self.index(start)?] = 0;
+        frontier.push(Reverse((heuristic(start), start.x, start.y)));
+
+        while let Some(Reverse((_, x, y))) = frontier.pop() {
+            let cell = IVec2::new(x, y);
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while let Some(previous) = came_from[self.index(current)?] {
+                    path.push(previous);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let cost = costs[self.index(cell)?];
+            for (neighbour, step) in self.neighbours(cell) {
+                let index = self.index(neighbour)?;
+                let new_cost = cost + step;
+                if new_cost < costs[index] {
+                    costs[index] = new_cost;
+                    came_from[index] = Some(cell);
+                    frontier.push(Reverse((
+                        new_cost + heuristic(neighbour),
+                        neighbour.x,
+                        neighbour.y,
+                    )));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Navigation queries in world coordinates for behaviours
+#[derive(SystemParam)]
+pub struct Navigation<'w> {
+    tiles: Res<'w, LandTiles>,
+    grid: Res<'w, NavGrid>,
+}
+
+impl Navigation<'_> {
+    /// Center of the next cell towards the player.
+    /// `None` if already in the player's cell or the player can't be reached
+    pub fn next_waypoint(&self, from: Vec2) -> Option<Vec2> {
+        let cell = self.tiles.world_to_array(from)?;
+        let next = self.grid.next_cell_to_player(cell)?;
+        Some(self.tiles.array_to_world(next))
+    }
+
+    /// Centers of cells from `from` to `to`, `None` if there is no path
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.tiles.world_to_array(from)?;
+        let goal = self.tiles.world_to_array(to)?;
+
+        let path = self.grid.find_path(start, goal)?;
+        Some(
+            path.into_iter()
+                .map(|cell| self.tiles.array_to_world(cell))
+                .collect(),
+        )
+    }
+
+    /// Player can be reached walking from the position
+    pub fn can_reach_player(&self, from: Vec2) -> bool {
+        self.tiles
+            .world_to_array(from)
+            .is_some_and(|cell| self.grid.flow_cost(cell).is_some())
+    }
+}
+
+fn reset_nav_grid(mut grid: ResMut<NavGrid>, tiles: Res<LandTiles>) {
+    *grid = NavGrid::new(tiles.size());
+
+    for (cell, _) in tiles.cells() {
+        grid.set_alive(cell, tiles.is_alive(cell));
+    }
+}
+
+fn update_nav_grid(mut grid: ResMut<NavGrid>, mut changed: EventReader<TileChanged>) {
+    for TileChanged { cell, alive } in changed.read() {
+        grid.set_alive(*cell, *alive);
+    }
+}
+
+fn block_animating_tiles(
+    mut grid: ResMut<NavGrid>,
+    visuals: Query<
+        (&TileVisual, &TileFadeAwayAnimation, &TileShowUpAnimation),
+        Or<(Changed<TileFadeAwayAnimation>, Changed<TileShowUpAnimation>)>,
+    >,
+) {
+    for (visual, fade_animation, show_animation) in visuals.iter() {
+        if fade_animation.is_enabled() || show_animation.is_enabled() {
+            grid.block(visual.cell);
+        } else {
+            grid.unblock(visual.cell);
+        }
+    }
+}
+
+fn update_flow_field(
+    mut grid: ResMut<NavGrid>,
+    tiles: Res<LandTiles>,
+    players: Query<&Transform, With<ActorOf<Player>>>,
+) {
+    let target = players
+        .iter()
+        .next()
+        .and_then(|player| tiles.world_to_array(player.translation.xy()));
+
+    if grid.target != target {
+        grid.target = target;
+        grid.dirty = true;
+    }
+
+    if grid.dirty {
+        grid.rebuild_flow();
+    }
+}
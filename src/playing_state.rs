@@ -1,11 +1,27 @@
 use std::time::Duration;
 
+use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy_consumable_event::{ConsumableEventApp, ConsumableEventReader, ConsumableEventWriter};
 
 use crate::{
-    actors::{dasher::Dasher, player::Player, SpawnActor},
-    common::animation::{disable::Disable, fade_away::FadeAway, show_up::ShowUp, Animation},
+    action_behaviour::{action_behaviour_schedule, actions::movement::MovementAction, ActionSet},
+    actors::{
+        definition::DataActor,
+        lifecycle::{DespawnActor, Despawning},
+        player::Player,
+        spawn_telegraph::TelegraphSpawn,
+        SpawnActor,
+    },
+    camera::ZoomPulse,
+    common::{
+        colliders::{Alignment, CollisionMask},
+        frame_animation::{clips, FrameAnimation},
+        walls::{Wall, WallCommands},
+    },
+    status_effects::{ApplyStatusEffect, StatusEffect, StatusEffects},
+    tilemap::{restore_cue::TileRestoreCueAnimation, TileFadeAwayAnimation, TileShowUpAnimation},
+    tuning::Tuning,
     GameState,
 };
 
@@ -13,42 +29,164 @@ pub struct PlayingPlugin;
 
 impl Plugin for PlayingPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Playing), (setup_layout, setup_tiles))
-            .add_systems(
-                Update,
-                (remove_tiles, tick_and_restore_tiles).run_if(in_state(GameState::Playing)),
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (apply_tiles_tuning, setup_layout.in_set(LayoutSetup)).chain(),
+        )
+        .add_systems(
+            Update,
+            (
+                remove_tiles,
+                (tick_and_restore_tiles, resolve_caught_by_restore).chain(),
+                knock_into_holes,
+                fall_off_arena,
             )
-            .add_systems(OnExit(GameState::Playing), cleanup_layout)
-            .init_resource::<LandTiles>()
-            .add_consumable_event::<RemoveTile>();
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            action_behaviour_schedule(),
+            update_airborne
+                .before(ActionSet)
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(OnExit(GameState::Playing), cleanup_layout)
+        .init_resource::<LandTiles>()
+        .init_resource::<LevelConfig>()
+        .add_consumable_event::<RemoveTile>()
+        .add_event::<TileChanged>()
+        .add_event::<CaughtByRestore>();
     }
 }
 
+/// Level layout is applied to [`LandTiles`] and actors are requested to spawn
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LayoutSetup;
+
+/// What happens to characters at the edges of the arena
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ArenaEdges {
+    /// Arena is surrounded by walls
+    #[default]
+    Walls,
+    /// Characters leaving the arena fall
+    FatalDrop,
+}
+
+/// Layout of the level, hand-made or [generated](crate::arena_generator).
+/// Positions are in world coordinates
+#[derive(Resource, Clone)]
+pub struct LevelConfig {
+    /// Number of tiles in each direction
+    pub size: UVec2,
+    /// Cells without ground, see [`LandTile::Void`]
+    pub voids: Vec<IVec2>,
+    pub edges: ArenaEdges,
+    /// Solid obstacles
+    pub pillars: Vec<Rect>,
+    pub player_spawn: Vec2,
+    pub enemy_spawns: Vec<Vec2>,
+}
+
+impl Default for LevelConfig {
+    fn default() -> Self {
+        Self {
+            size: UVec2::new(4, 4),
+            voids: Vec::new(),
+            edges: ArenaEdges::Walls,
+            pillars: vec![
+                Rect::from_center_size(Vec2::new(-40.0, 40.0), Vec2::splat(10.0)),
+                Rect::from_center_size(Vec2::new(40.0, -40.0), Vec2::splat(10.0)),
+            ],
+            player_spawn: Vec2::new(0.0, 0.0),
+            enemy_spawns: vec![Vec2::new(30.0, 30.0)],
+        }
+    }
+}
+
+const WALL_THICKNESS: f32 = 8.0;
+const WALL_COLOR: Color = Color::srgb(0.35, 0.3, 0.3);
+
 fn setup_layout(
+    mut commands: Commands,
     mut player_spawn: ConsumableEventWriter<SpawnActor<Player>>,
-    mut dasher_spawn: ConsumableEventWriter<SpawnActor<Dasher>>,
+    mut data_actor_spawn: ConsumableEventWriter<TelegraphSpawn<DataActor>>,
+    asset_server: Res<AssetServer>,
     mut tiles: ResMut<LandTiles>,
+    level: Res<LevelConfig>,
 ) {
-    tiles.reset();
+    tiles.reset(level.size, &level.voids);
+
+    let mut walls = level.pillars.clone();
+
+    if level.edges == ArenaEdges::Walls {
+        let arena = tiles.world_rect();
+        let outer = arena.inflate(WALL_THICKNESS);
+
+        walls.extend([
+            Rect::from_corners(outer.min, Vec2::new(outer.max.x, arena.min.y)),
+            Rect::from_corners(Vec2::new(outer.min.x, arena.max.y), outer.max),
+            Rect::from_corners(
+                Vec2::new(outer.min.x, arena.min.y),
+                Vec2::new(arena.min.x, arena.max.y),
+            ),
+            Rect::from_corners(
+                Vec2::new(arena.max.x, arena.min.y),
+                Vec2::new(outer.max.x, arena.max.y),
+            ),
+        ]);
+    }
+
+    for wall in walls {
+        let size = wall.size();
+
+        commands
+            .spawn(SpriteBundle {
+                sprite: Sprite {
+                    color: WALL_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(wall.center().extend(-5.0)),
+                ..default()
+            })
+            .wall(Collider::rectangle(size.x, size.y));
+    }
 
     player_spawn.send(SpawnActor(Player {
-        position: Vec2::new(0.0, 0.0),
+        position: level.player_spawn,
     }));
 
-    dasher_spawn.send(SpawnActor(Dasher {
-        position: Vec2::new(30.0, 30.0),
-    }))
+    let dasher = asset_server.load("actors/dasher.actor.ron");
+    for &position in &level.enemy_spawns {
+        data_actor_spawn.send(TelegraphSpawn(DataActor {
+            definition: dasher.clone(),
+            position,
+        }));
+    }
 }
 
-/// Tile size in pixels
-const TILE_SIZE: f32 = 40.;
-const TILES_WIDTH: usize = 4;
-const TILES_HEIGHT: usize = 4;
-
-/// Tiles between -80 and 80
-#[derive(Resource, Default)]
+/// Tiles centered around zero
+#[derive(Resource)]
 pub struct LandTiles {
-    tiles: [[LandTile; TILES_WIDTH]; TILES_HEIGHT],
+    /// Number of cells in each direction, set by [`LevelConfig`]
+    size: UVec2,
+    /// Column after column, see [`LandTiles::index`]
+    tiles: Vec<LandTile>,
+    /// Tile size in pixels, from [`TilesTuning`](crate::tuning::TilesTuning)
+    tile_size: f32,
+    /// [`TileVisual`](crate::tilemap::TileVisual) of each cell
+    visuals: Vec<Option<Entity>>,
+    /// Cells with [`LandTile::Destroyed`], only their timers are ticked
+    destroyed: Vec<IVec2>,
+}
+
+impl Default for LandTiles {
+    fn default() -> Self {
+        Self::new(
+            LevelConfig::default().size,
+            Tuning::default().tiles.tile_size,
+        )
+    }
 }
 
 #[derive(Default)]
@@ -58,156 +196,594 @@ pub enum LandTile {
     Destroyed {
         until_alive: Timer,
     },
+    /// There is no ground and it's never restored
+    Void,
 }
 
 impl LandTiles {
-    fn world_to_array(pos: Vec2) -> Option<IVec2> {
-        let scaled_array_position =
-            pos + Vec2::new(TILES_WIDTH as f32, TILES_HEIGHT as f32) * TILE_SIZE / 2.0;
+    #[inline]
+    fn index(&self, cell: IVec2) -> Option<usize> {
+        self.contains(cell)
+            .then(|| cell.x as usize * self.size.y as usize + cell.y as usize)
+    }
 
-        let array_pos = (scaled_array_position / TILE_SIZE).floor().as_ivec2();
+    /// Cell containing the position, may be outside of the grid
+    fn world_to_cell(&self, pos: Vec2) -> IVec2 {
+        let scaled_array_position = pos + self.size.as_vec2() * self.tile_size / 2.0;
 
-        if array_pos.x < 0
-            || array_pos.y < 0
-            || array_pos.x >= TILES_WIDTH as i32
-            || array_pos.y >= TILES_HEIGHT as i32
-        {
-            None
-        } else {
-            Some(array_pos)
-        }
+        (scaled_array_position / self.tile_size).floor().as_ivec2()
     }
 
-    fn array_to_world(pos: IVec2) -> Vec2 {
-        Vec2::new(
-            pos.x as f32 - TILES_WIDTH as f32 / 2.0,
-            pos.y as f32 - TILES_HEIGHT as f32 / 2.0,
-        ) * TILE_SIZE
-            + Vec2::splat(TILE_SIZE / 2.0)
+    /// Cell containing the position, `None` outside of the grid
+    pub fn world_to_array(&self, pos: Vec2) -> Option<IVec2> {
+        let array_pos = self.world_to_cell(pos);
+        self.contains(array_pos).then_some(array_pos)
+    }
+
+    /// Center of the cell in world coordinates
+    pub fn array_to_world(&self, pos: IVec2) -> Vec2 {
+        (pos.as_vec2() - self.size.as_vec2() / 2.0) * self.tile_size
+            + Vec2::splat(self.tile_size / 2.0)
     }
 }
 
 impl LandTiles {
-    pub fn reset(&mut self) {
-        *self = default();
+    /// Grid of alive tiles
+    pub fn new(size: UVec2, tile_size: f32) -> Self {
+        let mut tiles = Self {
+            size: UVec2::ZERO,
+            tiles: Vec::new(),
+            tile_size,
+            visuals: Vec::new(),
+            destroyed: Vec::new(),
+        };
+        tiles.reset(size, &[]);
+        tiles
+    }
+
+    /// Makes every tile alive except for `voids`, resizing the grid
+    pub fn reset(&mut self, size: UVec2, voids: &[IVec2]) {
+        let len = (size.x * size.y) as usize;
+
+        self.size = size;
+        self.tiles.clear();
+        self.tiles.resize_with(len, default);
+        self.visuals = vec![None; len];
+        self.destroyed.clear();
+
+        for &cell in voids {
+            if let Some(index) = self.index(cell) {
+                self.tiles[index] = LandTile::Void;
+            }
+        }
+    }
+
+    /// Entity with [`TileVisual`](crate::tilemap::TileVisual) of the cell
+    pub fn visual(&self, cell: IVec2) -> Option<Entity> {
+        self.index(cell).and_then(|index| self.visuals[index])
+    }
+
+    pub(crate) fn set_visual(&mut self, cell: IVec2, visual: Entity) {
+        if let Some(index) = self.index(cell) {
+            self.visuals[index] = Some(visual);
+        }
+    }
+
+    /// Cells that are currently destroyed
+    #[inline]
+    pub fn destroyed_cells(&self) -> &[IVec2] {
+        &self.destroyed
+    }
+
+    /// Area covered by tiles in world coordinates
+    pub fn world_rect(&self) -> Rect {
+        let half_size = self.size.as_vec2() * self.tile_size / 2.0;
+        Rect::from_center_half_size(Vec2::ZERO, half_size)
     }
 
     /// Returns true if postition is on alive tile, false othervise
     pub fn on_ground(&self, pos: Vec2) -> bool {
-        let array_position = LandTiles::world_to_array(pos);
+        self.world_to_array(pos)
+            .is_some_and(|array_position| self.is_alive(array_position))
+    }
 
-        let Some(array_position) = array_position else {
-            return false;
-        };
+    /// Returns true if cell is inside the grid and alive
+    pub fn is_alive(&self, cell: IVec2) -> bool {
+        matches!(self.tile(cell), Some(LandTile::Alive))
+    }
 
-        match self.tiles[array_position.x as usize][array_position.y as usize] {
-            LandTile::Alive => true,
-            LandTile::Destroyed { .. } => false,
-        }
+    /// Centers of alive tiles in world coordinates
+    pub fn alive_tile_centers(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.cells()
+            .filter(|(_, tile)| matches!(tile, LandTile::Alive))
+            .map(|(cell, _)| self.array_to_world(cell))
     }
 }
 
-#[derive(Component)]
-pub struct TileSprite {
-    x: usize,
-    y: usize,
-}
+/// Offsets of the 4 orthogonal neighbours
+const NEIGHBOURS_4: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+/// Offsets of the 8 surrounding neighbours
+const NEIGHBOURS_8: [IVec2; 8] = [
+    IVec2::X,
+    IVec2::NEG_X,
+    IVec2::Y,
+    IVec2::NEG_Y,
+    IVec2::new(1, 1),
+    IVec2::new(1, -1),
+    IVec2::new(-1, 1),
+    IVec2::new(-1, -1),
+];
+
+/// Grid queries. Cells are `IVec2` grid coordinates, see [`LandTiles::world_to_array`]
+impl LandTiles {
+    /// Number of cells in each direction
+    #[inline]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
 
-const FALL_ANIMATION_DURATION: Duration = Duration::from_millis(300);
-const RESTORE_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+    #[inline]
+    pub fn tile_size(&self) -> f32 {
+        self.tile_size
+    }
 
-pub type FadeAwayAnimation = Animation<FadeAway, Disable<FadeAway>>;
-pub type ShowUpAnimation = Animation<ShowUp, Disable<ShowUp>>;
+    #[inline]
+    pub fn contains(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.size.x as i32 && cell.y < self.size.y as i32
+    }
 
-fn setup_tiles(mut commands: Commands, loader: Res<AssetServer>) {
-    for x in 0..TILES_WIDTH {
-        for y in 0..TILES_HEIGHT {
-            let fade_away_timer = Timer::new(FALL_ANIMATION_DURATION, TimerMode::Once);
-            let show_up_timer = Timer::new(RESTORE_ANIMATION_DURATION, TimerMode::Once);
+    /// Tile of the cell, `None` outside of the grid
+    pub fn tile(&self, cell: IVec2) -> Option<&LandTile> {
+        self.index(cell).map(|index| &self.tiles[index])
+    }
 
-            commands.spawn((
-                SpriteBundle {
-                    texture: loader.load("textures/ground_tile.png"),
-                    transform: Transform::from_translation(
-                        LandTiles::array_to_world(IVec2::new(x as i32, y as i32)).extend(-10.0),
-                    ),
-                    ..default()
-                },
-                TileSprite { x, y },
-                FadeAwayAnimation::new_disabled(fade_away_timer),
-                ShowUpAnimation::new_disabled(show_up_timer),
-            ));
+    /// All cells with their tiles
+    pub fn cells(&self) -> impl Iterator<Item = (IVec2, &LandTile)> + '_ {
+        let height = self.size.y as usize;
+        self.tiles.iter().enumerate().map(move |(index, tile)| {
+            (
+                IVec2::new((index / height) as i32, (index % height) as i32),
+                tile,
+            )
+        })
+    }
+
+    /// Orthogonal neighbours inside the grid
+    pub fn neighbours_4(&self, cell: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .map(move |&offset| cell + offset)
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// Orthogonal and diagonal neighbours inside the grid
+    pub fn neighbours_8(&self, cell: IVec2) -> impl Iterator<Item = IVec2> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .map(move |&offset| cell + offset)
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// Cells overlapping the world rect, clamped to the grid
+    pub fn cells_in_rect(&self, rect: Rect) -> impl Iterator<Item = IVec2> {
+        let max_cell = self.size.as_ivec2() - IVec2::ONE;
+        let min = self.world_to_cell(rect.min).max(IVec2::ZERO);
+        let max = self.world_to_cell(rect.max).min(max_cell);
+
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+    }
+
+    /// Cells on the line between `from` and `to` including both, in order.
+    /// Uses Bresenham's algorithm and may go outside of the grid
+    pub fn line(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+        let delta = (to - from).abs();
+        let step = (to - from).signum();
+        let mut error = delta.x - delta.y;
+        let mut current = Some(from);
+
+        std::iter::from_fn(move || {
+            let cell = current?;
+
+            current = if cell == to {
+                None
+            } else {
+                let mut next = cell;
+                let doubled = error * 2;
+                if doubled > -delta.y {
+                    error -= delta.y;
+                    next.x += step.x;
+                }
+                if doubled < delta.x {
+                    error += delta.x;
+                    next.y += step.y;
+                }
+                Some(next)
+            };
+
+            Some(cell)
+        })
+    }
+
+    /// First cell on the way from `from` to `to` that isn't alive ground.
+    /// `None` if the whole way is on alive tiles
+    pub fn raycast(&self, from: Vec2, to: Vec2) -> Option<IVec2> {
+        Self::line(self.world_to_cell(from), self.world_to_cell(to))
+            .find(|&cell| !self.is_alive(cell))
+    }
+
+    /// Alive cells connected to `cell` orthogonally, including it.
+    /// Empty if the cell isn't alive
+    pub fn island(&self, cell: IVec2) -> Vec<IVec2> {
+        self.fill_island(cell, &mut vec![false; self.tiles.len()])
+    }
+
+    /// All groups of connected alive cells
+    pub fn islands(&self) -> Vec<Vec<IVec2>> {
+        // Shared by all islands, so every cell is visited once
+        let mut visited = vec![false; self.tiles.len()];
+        let mut islands = Vec::new();
+
+        for (cell, _) in self.cells() {
+            let island = self.fill_island(cell, &mut visited);
+            if !island.is_empty() {
+                islands.push(island);
+            }
         }
+
+        islands
     }
+
+    /// [`island`](Self::island) of the cell, skipping and marking cells in `visited`,
+    /// which is indexed like the tiles
+    fn fill_island(&self, cell: IVec2, visited: &mut [bool]) -> Vec<IVec2> {
+        let Some(index) = self.index(cell) else {
+            return Vec::new();
+        };
+        if visited[index] || !self.is_alive(cell) {
+            return Vec::new();
+        }
+        visited[index] = true;
+
+        let mut island = vec![cell];
+        let mut index = 0;
+
+        while let Some(&current) = island.get(index) {
+            index += 1;
+
+            for neighbour in self.neighbours_4(current) {
+                let Some(index) = self.index(neighbour) else {
+                    continue;
+                };
+                if !visited[index] && self.is_alive(neighbour) {
+                    visited[index] = true;
+                    island.push(neighbour);
+                }
+            }
+        }
+
+        island
+    }
+
+    /// Both positions are on alive ground connected orthogonally.
+    /// Actor that can't reach the player this way is stranded
+    pub fn same_island(&self, a: Vec2, b: Vec2) -> bool {
+        let (Some(a), Some(b)) = (self.world_to_array(a), self.world_to_array(b)) else {
+            return false;
+        };
+
+        self.island(a).contains(&b)
+    }
+}
+
+/// Camera zoom when tile breaks
+const REMOVE_TILE_ZOOM_PULSE: f32 = 0.03;
+const REMOVE_TILE_ZOOM_PULSE_DURATION: Duration = Duration::from_millis(150);
+
+/// Tile size is only applied when a run starts,
+/// changing it during a run would move the arena from under the actors
+fn apply_tiles_tuning(tuning: Res<Tuning>, mut tiles: ResMut<LandTiles>) {
+    tiles.tile_size = tuning.tiles.tile_size;
 }
 
 #[derive(Event)]
 pub struct RemoveTile(pub Vec2, pub Duration);
 
-fn remove_tiles(
+/// Tile was destroyed or restored
+#[derive(Event, Clone, Copy)]
+pub struct TileChanged {
+    pub cell: IVec2,
+    pub alive: bool,
+}
+
+/// Destroys tiles at [`RemoveTile`] positions, public for the `land_tiles` bench
+pub fn remove_tiles(
     mut tiles: ResMut<LandTiles>,
     mut remove_event: ConsumableEventReader<RemoveTile>,
-    mut tile_sprite_query: Query<(&TileSprite, &mut FadeAwayAnimation, &mut ShowUpAnimation)>,
+    mut changed: EventWriter<TileChanged>,
+    mut zoom_pulse: ConsumableEventWriter<ZoomPulse>,
+    mut tile_visual_query: Query<(
+        &mut TileFadeAwayAnimation,
+        &mut TileShowUpAnimation,
+        &mut TileRestoreCueAnimation,
+    )>,
 ) {
     for RemoveTile(pos, duration) in remove_event.read_and_consume_all() {
-        let Some(array_pos) = LandTiles::world_to_array(pos) else {
-            return;
+        let Some(array_pos) = tiles.world_to_array(pos) else {
+            continue;
         };
 
-        let tile: &mut LandTile = &mut tiles.tiles[array_pos.x as usize][array_pos.y as usize];
+        let Some(index) = tiles.index(array_pos) else {
+            continue;
+        };
+        let tile: &mut LandTile = &mut tiles.tiles[index];
 
         match tile {
             LandTile::Alive => {
                 *tile = LandTile::Destroyed {
                     until_alive: Timer::new(duration, TimerMode::Once),
                 };
-
-                let animation = tile_sprite_query.iter_mut().find(|(tile_sprite, _, _)| {
-                    tile_sprite.x as i32 == array_pos.x && tile_sprite.y as i32 == array_pos.y
+                tiles.destroyed.push(array_pos);
+                changed.send(TileChanged {
+                    cell: array_pos,
+                    alive: false,
                 });
 
-                if let Some((_, mut fade_animation, mut show_animation)) = animation {
+                let animation = tiles
+                    .visual(array_pos)
+                    .and_then(|visual| tile_visual_query.get_mut(visual).ok());
+
+                if let Some((mut fade_animation, mut show_animation, _)) = animation {
                     show_animation.disable();
                     fade_animation.enable();
                 }
+
+                zoom_pulse.send(ZoomPulse {
+                    amount: REMOVE_TILE_ZOOM_PULSE,
+                    duration: REMOVE_TILE_ZOOM_PULSE_DURATION,
+                });
             }
             LandTile::Destroyed { until_alive } => {
                 until_alive.set_duration(duration);
                 until_alive.reset();
             }
+            LandTile::Void => continue,
+        }
+
+        // Restore time starts over in both cases
+        let restore_cue = tiles
+            .visual(array_pos)
+            .and_then(|visual| tile_visual_query.get_mut(visual).ok());
+
+        if let Some((_, _, mut restore_cue)) = restore_cue {
+            restore_cue.set_duration(duration);
+            restore_cue.enable();
         }
     }
 }
 
-fn tick_and_restore_tiles(
+/// Restores destroyed tiles whose time is up, public for the `land_tiles` bench
+pub fn tick_and_restore_tiles(
     time: Res<Time>,
     mut tiles: ResMut<LandTiles>,
-    mut tile_sprite_query: Query<(&TileSprite, &mut FadeAwayAnimation, &mut ShowUpAnimation)>,
+    mut changed: EventWriter<TileChanged>,
+    mut tile_visual_query: Query<(&mut TileFadeAwayAnimation, &mut TileShowUpAnimation)>,
 ) {
-    for x in 0..TILES_WIDTH {
-        for y in 0..TILES_HEIGHT {
-            let tile: &mut LandTile = &mut tiles.tiles[x][y];
-
-            match tile {
-                LandTile::Alive => (),
-                LandTile::Destroyed { until_alive } => {
-                    until_alive.tick(time.delta());
-                    if until_alive.finished() {
-                        *tile = LandTile::Alive;
-
-                        let animation = tile_sprite_query
-                            .iter_mut()
-                            .find(|(tile_sprite, _, _)| tile_sprite.x == x && tile_sprite.y == y);
-
-                        if let Some((_, mut fade_animation, mut show_animation)) = animation {
-                            fade_animation.disable();
-                            show_animation.enable();
-                        }
-                    }
-                }
+    let tiles = &mut *tiles;
+    let mut destroyed_index = 0;
+
+    while let Some(&cell) = tiles.destroyed.get(destroyed_index) {
+        let Some(tile_index) = tiles.index(cell) else {
+            tiles.destroyed.swap_remove(destroyed_index);
+            continue;
+        };
+        let tile: &mut LandTile = &mut tiles.tiles[tile_index];
+
+        let LandTile::Destroyed { until_alive } = tile else {
+            tiles.destroyed.swap_remove(destroyed_index);
+            continue;
+        };
+
+        until_alive.tick(time.delta());
+        if !until_alive.finished() {
+            destroyed_index += 1;
+            continue;
+        }
+
+        *tile = LandTile::Alive;
+        tiles.destroyed.swap_remove(destroyed_index);
+        changed.send(TileChanged { cell, alive: true });
+
+        let animation = tiles
+            .visual(cell)
+            .and_then(|visual| tile_visual_query.get_mut(visual).ok());
+
+        if let Some((mut fade_animation, mut show_animation)) = animation {
+            fade_animation.disable();
+            show_animation.enable();
+        }
+    }
+}
+
+/// What happens to a character standing in a hole when the tile is restored
+#[derive(Reflect, Clone, Copy, PartialEq, Debug)]
+pub enum CaughtByRestoreRule {
+    /// Moved to the closest alive tile
+    Eject,
+    /// Is stunned. `damage` is only reported in [`CaughtByRestoreOutcome::Crushed`],
+    /// characters have no health to apply it to yet
+    Crush { damage: f32, stun_duration: f32 },
+    /// Stays on the restored tile and is slowed while climbing out
+    ClimbOut { slow: f32, duration: f32 },
+}
+
+/// Outcome for one character caught by the restored tile
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CaughtByRestoreOutcome {
+    Ejected {
+        to: Vec2,
+    },
+    /// Nothing applies the damage, characters have no health yet
+    Crushed {
+        damage: f32,
+    },
+    ClimbedOut,
+}
+
+/// Character was inside the hole when `cell` was restored
+#[derive(Event, Clone, Copy)]
+pub struct CaughtByRestore {
+    pub entity: Entity,
+    pub cell: IVec2,
+    pub outcome: CaughtByRestoreOutcome,
+}
+
+fn resolve_caught_by_restore(
+    mut restored: EventReader<TileChanged>,
+    tiles: Res<LandTiles>,
+    tuning: Res<Tuning>,
+    mut characters: Query<(Entity, &mut Transform, &CollisionLayers), Without<Despawning>>,
+    mut effects: ConsumableEventWriter<ApplyStatusEffect>,
+    mut caught: EventWriter<CaughtByRestore>,
+) {
+    for TileChanged { cell, alive } in restored.read() {
+        if !alive {
+            continue;
+        }
+
+        for (entity, mut transform, layers) in characters.iter_mut() {
+            if layers.memberships & CollisionMask::Character == LayerMask::NONE
+                || tiles.world_to_array(transform.translation.xy()) != Some(*cell)
+            {
+                continue;
             }
+
+            let outcome = match tuning.tiles.caught_by_restore {
+                CaughtByRestoreRule::Eject => {
+                    let position = transform.translation.xy();
+                    let closest = tiles
+                        .alive_tile_centers()
+                        .filter(|&center| tiles.world_to_array(center) != Some(*cell))
+                        .min_by(|a, b| {
+                            a.distance_squared(position)
+                                .total_cmp(&b.distance_squared(position))
+                        });
+
+                    // Whole arena is this tile, nowhere to go
+                    let Some(to) = closest else {
+                        continue;
+                    };
+
+                    transform.translation = to.extend(transform.translation.z);
+                    CaughtByRestoreOutcome::Ejected { to }
+                }
+                CaughtByRestoreRule::Crush {
+                    damage,
+                    stun_duration,
+                } => {
+                    effects.send(ApplyStatusEffect {
+                        entity,
+                        effect: StatusEffect::stun(Duration::from_secs_f32(stun_duration)),
+                    });
+                    CaughtByRestoreOutcome::Crushed { damage }
+                }
+                CaughtByRestoreRule::ClimbOut { slow, duration } => {
+                    effects.send(ApplyStatusEffect {
+                        entity,
+                        effect: StatusEffect::slow(slow, Duration::from_secs_f32(duration)),
+                    });
+                    CaughtByRestoreOutcome::ClimbedOut
+                }
+            };
+
+            caught.send(CaughtByRestore {
+                entity,
+                cell: *cell,
+                outcome,
+            });
         }
     }
 }
 
-fn cleanup_layout() {}
+fn update_airborne(tiles: Res<LandTiles>, mut query: Query<(&Transform, &mut MovementAction)>) {
+    for (transform, mut movement) in query.iter_mut() {
+        let airborne = !tiles.on_ground(transform.translation.xy());
+        if movement.airborne != airborne {
+            movement.airborne = airborne;
+        }
+    }
+}
+
+/// Enemies knocked back over a destroyed tile fall, e.g. after a
+/// [`PlayerStomp`](crate::actors::player::stomp::PlayerStomp)
+fn knock_into_holes(
+    tiles: Res<LandTiles>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &StatusEffects,
+            &CollisionLayers,
+            &Alignment,
+            Option<&mut FrameAnimation>,
+        ),
+        Without<Despawning>,
+    >,
+    mut despawn: ConsumableEventWriter<DespawnActor>,
+) {
+    for (entity, transform, effects, layers, alignment, animation) in query.iter_mut() {
+        // Hurtbox layers are removed while invulnerable, so enemies are told by the alignment
+        if effects.knockback().is_none()
+            || layers.memberships & CollisionMask::Character == LayerMask::NONE
+            || *alignment != Alignment::Enemy
+            || tiles.on_ground(transform.translation.xy())
+        {
+            continue;
+        }
+
+        if let Some(mut animation) = animation {
+            animation.play(clips::FALL);
+        }
+        despawn.send(DespawnActor(entity));
+    }
+}
+
+/// With [`ArenaEdges::FatalDrop`] characters leaving the arena fall
+fn fall_off_arena(
+    tiles: Res<LandTiles>,
+    level: Res<LevelConfig>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &CollisionLayers,
+            Option<&mut FrameAnimation>,
+        ),
+        Without<Despawning>,
+    >,
+    mut despawn: ConsumableEventWriter<DespawnActor>,
+) {
+    if level.edges != ArenaEdges::FatalDrop {
+        return;
+    }
+
+    let arena = tiles.world_rect();
+
+    for (entity, transform, layers, animation) in query.iter_mut() {
+        if layers.memberships & CollisionMask::Character == LayerMask::NONE
+            || arena.contains(transform.translation.xy())
+        {
+            continue;
+        }
+
+        if let Some(mut animation) = animation {
+            animation.play(clips::FALL);
+        }
+        despawn.send(DespawnActor(entity));
+    }
+}
+
+fn cleanup_layout(mut commands: Commands, walls: Query<Entity, With<Wall>>) {
+    for wall in walls.iter() {
+        commands.entity(wall).despawn_recursive();
+    }
+}
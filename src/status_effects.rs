@@ -0,0 +1,290 @@
+//! Timed effects changing how actors move and interact.
+//!
+//! Effects are applied with [`ApplyStatusEffect`] or directly through [`StatusEffects::apply`].
+//! Stacking rules:
+//! - [`StatusEffectKind::Stun`] and [`StatusEffectKind::Invulnerability`] don't stack,
+//!   the longer of remaining and new duration is kept
+//! - [`StatusEffectKind::Slow`] of different strength coexist and the strongest one applies,
+//!   slow of the same strength is refreshed
+//! - [`StatusEffectKind::Knockback`] replaces the previous knockback
+
+use avian2d::prelude::*;
+use bevy::{prelude::*, utils::Duration};
+use bevy_consumable_event::{ConsumableEventApp, ConsumableEventReader};
+
+use crate::{
+    action_behaviour::{
+        action_behaviour_schedule, actions::movement::MovementAction, BehaviourSet,
+    },
+    common::colliders::CollisionMask,
+    GameState,
+};
+
+pub struct StatusEffectsPlugin;
+
+impl Plugin for StatusEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_consumable_event::<ApplyStatusEffect>().add_systems(
+            action_behaviour_schedule(),
+            (
+                apply_status_effect_events,
+                tick_status_effects,
+                (
+                    apply_stun,
+                    apply_movement_effects,
+                    apply_knockback_velocity,
+                    apply_invulnerability,
+                ),
+            )
+                .chain()
+                .before(BehaviourSet)
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum StatusEffectKind {
+    /// Behaviours of the entity don't run and it doesn't move by itself
+    Stun,
+    /// Multiplies max speed of [`MovementAction`]
+    Slow(f32),
+    /// Velocity of the entity, decreasing to zero by the end of the effect
+    Knockback(Vec2),
+    /// Hurtbox doesn't collide with hitboxes
+    Invulnerability,
+}
+
+#[derive(Clone, Debug)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub timer: Timer,
+}
+
+impl StatusEffect {
+    #[inline]
+    pub fn new(kind: StatusEffectKind, duration: Duration) -> Self {
+        Self {
+            kind,
+            timer: Timer::new(duration, TimerMode::Once),
+        }
+    }
+
+    #[inline]
+    pub fn stun(duration: Duration) -> Self {
+        Self::new(StatusEffectKind::Stun, duration)
+    }
+
+    #[inline]
+    pub fn slow(multiplier: f32, duration: Duration) -> Self {
+        Self::new(StatusEffectKind::Slow(multiplier), duration)
+    }
+
+    #[inline]
+    pub fn knockback(velocity: Vec2, duration: Duration) -> Self {
+        Self::new(StatusEffectKind::Knockback(velocity), duration)
+    }
+
+    #[inline]
+    pub fn invulnerability(duration: Duration) -> Self {
+        Self::new(StatusEffectKind::Invulnerability, duration)
+    }
+}
+
+/// Active effects of the entity
+#[derive(Component)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+    /// Hurtbox layers removed by invulnerability
+    hidden_hurtbox: LayerMask,
+}
+
+impl Default for StatusEffects {
+    fn default() -> Self {
+        Self {
+            effects: Vec::new(),
+            hidden_hurtbox: LayerMask::NONE,
+        }
+    }
+}
+
+impl StatusEffects {
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let existing =
+            self.effects
+                .iter_mut()
+                .find(|existing| match (existing.kind, effect.kind) {
+                    (StatusEffectKind::Slow(a), StatusEffectKind::Slow(b)) => a == b,
+                    (a, b) => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+                });
+
+        let Some(existing) = existing else {
+            self.effects.push(effect);
+            return;
+        };
+
+        match effect.kind {
+            StatusEffectKind::Knockback(_) => *existing = effect,
+            _ => {
+                if effect.timer.remaining() > existing.timer.remaining() {
+                    *existing = effect;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn is_stunned(&self) -> bool {
+        self.has(StatusEffectKind::Stun)
+    }
+
+    pub fn is_invulnerable(&self) -> bool {
+        self.has(StatusEffectKind::Invulnerability)
+    }
+
+    /// Multiplier of the strongest slow, `1.0` if not slowed
+    pub fn speed_multiplier(&self) -> f32 {
+        self.effects
+            .iter()
+            .filter_map(|effect| match effect.kind {
+                StatusEffectKind::Slow(multiplier) => Some(multiplier),
+                _ => None,
+            })
+            .fold(1.0, f32::min)
+    }
+
+    /// Current knockback velocity
+    pub fn knockback(&self) -> Option<Vec2> {
+        self.effects.iter().find_map(|effect| match effect.kind {
+            StatusEffectKind::Knockback(velocity) => {
+                Some(velocity * (1. - effect.timer.fraction()))
+            }
+            _ => None,
+        })
+    }
+
+    fn has(&self, kind: StatusEffectKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+}
+
+/// Applies `effect` to the `entity`, adding [`StatusEffects`] if needed
+#[derive(Event)]
+pub struct ApplyStatusEffect {
+    pub entity: Entity,
+    pub effect: StatusEffect,
+}
+
+/// Behaviours of entities with this marker shouldn't run. Added and removed with stun
+#[derive(Component)]
+pub struct Stunned;
+
+fn apply_status_effect_events(
+    mut commands: Commands,
+    mut events: ConsumableEventReader<ApplyStatusEffect>,
+    mut query: Query<&mut StatusEffects>,
+) {
+    for ApplyStatusEffect { entity, effect } in events.read_and_consume_all() {
+        if let Ok(mut effects) = query.get_mut(entity) {
+            effects.apply(effect);
+            continue;
+        }
+
+        let Some(mut entity) = commands.get_entity(entity) else {
+            continue;
+        };
+
+        let mut effects = StatusEffects::default();
+        effects.apply(effect);
+        entity.insert(effects);
+    }
+}
+
+fn tick_status_effects(time: Res<Time>, mut query: Query<&mut StatusEffects>) {
+    for mut effects in query.iter_mut() {
+        if effects.effects.is_empty() {
+            continue;
+        }
+
+        for effect in effects.effects.iter_mut() {
+            effect.timer.tick(time.delta());
+        }
+        effects.effects.retain(|effect| !effect.timer.finished());
+    }
+}
+
+fn apply_stun(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &StatusEffects,
+            Has<Stunned>,
+            Option<&mut MovementAction>,
+        ),
+        Changed<StatusEffects>,
+    >,
+) {
+    for (entity, effects, was_stunned, movement) in query.iter_mut() {
+        let stunned = effects.is_stunned();
+
+        if stunned && !was_stunned {
+            commands.entity(entity).insert(Stunned);
+        } else if !stunned && was_stunned {
+            commands.entity(entity).remove::<Stunned>();
+        }
+
+        // Behaviour won't update the direction anymore
+        if let (true, Some(mut movement)) = (stunned, movement) {
+            movement.direction = Vec2::ZERO;
+        }
+    }
+}
+
+fn apply_movement_effects(
+    mut query: Query<(&StatusEffects, &mut MovementAction), Changed<StatusEffects>>,
+) {
+    for (effects, mut movement) in query.iter_mut() {
+        movement.speed_multiplier = effects.speed_multiplier();
+        movement.override_velocity = effects.knockback();
+    }
+}
+
+/// Bodies without [`MovementAction`] don't move by themselves, so only knockback moves them
+fn apply_knockback_velocity(
+    mut query: Query<
+        (&StatusEffects, &mut LinearVelocity),
+        (Without<MovementAction>, Changed<StatusEffects>),
+    >,
+) {
+    for (effects, mut velocity) in query.iter_mut() {
+        velocity.0 = effects.knockback().unwrap_or(Vec2::ZERO);
+    }
+}
+
+fn apply_invulnerability(
+    mut query: Query<(&mut StatusEffects, &mut CollisionLayers), Changed<StatusEffects>>,
+) {
+    let hurtboxes = LayerMask::from([CollisionMask::PlayerHurtbox, CollisionMask::EnemyHurtbox]);
+
+    for (mut effects, mut layers) in query.iter_mut() {
+        let invulnerable = effects.is_invulnerable();
+
+        if invulnerable && effects.hidden_hurtbox == LayerMask::NONE {
+            let hidden = layers.memberships & hurtboxes;
+            layers.memberships &= !hurtboxes;
+            effects.hidden_hurtbox = hidden;
+        } else if !invulnerable && effects.hidden_hurtbox != LayerMask::NONE {
+            layers.memberships |= effects.hidden_hurtbox;
+            effects.hidden_hurtbox = LayerMask::NONE;
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Picks tile variants from the tileset by the state of 8 neighbours.
+//!
+//! Rules are defined in a `.tileset.ron` file next to the tileset texture. Tiles around a changed
+//! cell are retiled on [`TileChanged`], all tiles are retiled when the tileset is (re)loaded.
+
+use bevy::{prelude::*, utils::HashSet};
+
+use super::{TileChunks, TileVisual};
+use crate::{
+    common::reflect_ron::{ReflectRonApp, ReflectRonAsset},
+    playing_state::{LandTiles, TileChanged},
+    GameState,
+};
+
+pub(super) fn register_autotiling(app: &mut App) {
+    app.init_reflect_ron_asset::<Tileset>()
+        .init_resource::<GroundTileset>()
+        .add_systems(
+            Update,
+            (apply_tileset_texture, autotile).run_if(in_state(GameState::Playing)),
+        );
+}
+
+const GROUND_TILESET: &str = "textures/ground.tileset.ron";
+
+/// Texture split into `columns` x `rows` tiles and rules choosing between them
+#[derive(Asset, Reflect)]
+pub struct Tileset {
+    pub texture: String,
+    pub columns: u32,
+    pub rows: u32,
+    /// Tile used when no rule matches
+    pub default: u32,
+    /// The first matching rule is used
+    pub rules: Vec<TileRule>,
+}
+
+impl ReflectRonAsset for Tileset {
+    const EXTENSIONS: &'static [&'static str] = &["tileset.ron"];
+}
+
+/// Pattern of neighbours, rows go from north to south.
+/// `#` is ground, `.` is a hole or outside of the grid, anything else or missing symbols match both.
+/// The centre is the tile itself, which is always ground, so it's ignored
+#[derive(Reflect)]
+pub struct TileRule {
+    pub pattern: Vec<String>,
+    pub tile: u32,
+}
+
+impl TileRule {
+    /// `ground` is the state of 3x3 cells around the tile, rows from north to south
+    fn matches(&self, ground: [[bool; 3]; 3]) -> bool {
+        self.pattern
+            .iter()
+            .zip(ground)
+            .enumerate()
+            .all(|(row, (pattern, ground))| {
+                pattern
+                    .chars()
+                    .zip(ground)
+                    .enumerate()
+                    .all(|(column, (symbol, ground))| match symbol {
+                        _ if (row, column) == (1, 1) => true,
+                        '#' => ground,
+                        '.' => !ground,
+                        _ => true,
+                    })
+            })
+    }
+}
+
+impl Tileset {
+    /// Tile for the cell of the grid
+    pub fn tile_for(&self, tiles: &LandTiles, cell: IVec2) -> u32 {
+        let mut ground = [[false; 3]; 3];
+        for (row, ground_row) in ground.iter_mut().enumerate() {
+            for (column, ground) in ground_row.iter_mut().enumerate() {
+                let offset = IVec2::new(column as i32 - 1, 1 - row as i32);
+                *ground = tiles.is_alive(cell + offset);
+            }
+        }
+
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(ground))
+            .map_or(self.default, |rule| rule.tile)
+    }
+
+    /// Normalized texture rect of the tile
+    pub fn uv(&self, tile: u32) -> Rect {
+        let size = Vec2::new(1. / self.columns as f32, 1. / self.rows as f32);
+        let min = Vec2::new((tile % self.columns) as f32, (tile / self.columns) as f32) * size;
+        Rect::from_corners(min, min + size)
+    }
+}
+
+#[derive(Resource)]
+pub struct GroundTileset(pub Handle<Tileset>);
+
+impl FromWorld for GroundTileset {
+    fn from_world(world: &mut World) -> Self {
+        Self(world.resource::<AssetServer>().load(GROUND_TILESET))
+    }
+}
+
+/// Tileset was loaded or changed
+fn tileset_reloaded(
+    events: &mut EventReader<AssetEvent<Tileset>>,
+    tileset: &GroundTileset,
+) -> bool {
+    events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => {
+            *id == tileset.0.id()
+        }
+        _ => false,
+    })
+}
+
+/// Applies tileset texture when the tileset or tiles are (re)created
+fn apply_tileset_texture(
+    mut events: EventReader<AssetEvent<Tileset>>,
+    tileset: Res<GroundTileset>,
+    tilesets: Res<Assets<Tileset>>,
+    asset_server: Res<AssetServer>,
+    chunks: Res<TileChunks>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    added: Query<(), Added<TileVisual>>,
+) {
+    let reloaded = tileset_reloaded(&mut events, &tileset);
+    if !reloaded && added.is_empty() {
+        return;
+    }
+
+    // `get_mut` marks the material as modified, so it's only called when there is a change
+    let (Some(tileset), Some(material)) = (
+        tilesets.get(&tileset.0),
+        materials.get_mut(&chunks.material),
+    ) else {
+        return;
+    };
+
+    material.texture = Some(asset_server.load(tileset.texture.clone()));
+}
+
+/// Retiles cells around changed tiles, everything when the tileset or tiles are (re)created
+fn autotile(
+    mut events: EventReader<AssetEvent<Tileset>>,
+    mut changed: EventReader<TileChanged>,
+    tileset: Res<GroundTileset>,
+    tilesets: Res<Assets<Tileset>>,
+    tiles: Res<LandTiles>,
+    added: Query<(), Added<TileVisual>>,
+    mut visuals: Query<&mut TileVisual>,
+) {
+    let reloaded = tileset_reloaded(&mut events, &tileset);
+
+    let Some(tileset) = tilesets.get(&tileset.0) else {
+        return;
+    };
+
+    let mut to_retile: HashSet<IVec2> = HashSet::default();
+
+    if reloaded || !added.is_empty() {
+        to_retile.extend(tiles.cells().map(|(cell, _)| cell));
+    }
+    for TileChanged { cell, .. } in changed.read() {
+        to_retile.insert(*cell);
+        to_retile.extend(tiles.neighbours_8(*cell));
+    }
+
+    for cell in to_retile {
+        let Some(mut visual) = tiles
+            .visual(cell)
+            .and_then(|entity| visuals.get_mut(entity).ok())
+        else {
+            continue;
+        };
+
+        let uv = tileset.uv(tileset.tile_for(&tiles, cell));
+        if visual.uv != uv {
+            visual.uv = uv;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: [&str; 3]) -> TileRule {
+        TileRule {
+            pattern: pattern.map(String::from).to_vec(),
+            tile: 0,
+        }
+    }
+
+    #[test]
+    fn centre_of_pattern_is_ignored() {
+        let ground = [
+            [false, true, false],
+            [true, false, true],
+            [false, true, false],
+        ];
+
+        assert!(rule(["?#?", "#.#", "?#?"]).matches(ground));
+        assert!(rule(["?#?", "###", "?#?"]).matches(ground));
+        assert!(!rule(["?.?", "###", "?#?"]).matches(ground));
+    }
+}
@@ -0,0 +1,311 @@
+//! Rendering of [`LandTiles`] as chunked meshes.
+//!
+//! Each tile has an entity with [`TileVisual`] that holds it's alpha and UVs and is animated like a
+//! sprite would be. Tiles are drawn by one mesh per [`CHUNK_SIZE`]x[`CHUNK_SIZE`] chunk,
+//! only chunks with changed tiles are rebuilt. Tile variants are picked by [`autotile`].
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    utils::{Duration, HashSet},
+};
+
+use crate::{
+    common::animation::{disable::Disable, Animation},
+    dynamic_initialization::{DataItem, EntitySystem, ParamItem},
+    playing_state::{LandTile, LandTiles, LayoutSetup},
+    tuning::Tuning,
+    GameState,
+};
+
+pub mod autotile;
+pub mod restore_cue;
+
+use restore_cue::{TileRestoreCueAnimation, RESTORE_CUE_COLOR};
+
+pub struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        autotile::register_autotiling(app);
+
+        app.init_resource::<TileChunks>()
+            .add_systems(OnEnter(GameState::Playing), setup_tiles.after(LayoutSetup))
+            .add_systems(
+                Update,
+                apply_tilemap_tuning.run_if(resource_changed::<Tuning>),
+            )
+            .add_systems(PostUpdate, rebuild_dirty_chunks)
+            .add_systems(OnExit(GameState::Playing), cleanup_tiles);
+    }
+}
+
+/// Tiles in each direction of a chunk
+pub const CHUNK_SIZE: usize = 16;
+
+/// Drawn behind actors
+const TILES_Z: f32 = -10.0;
+
+/// Visual state of one tile
+#[derive(Component)]
+pub struct TileVisual {
+    pub cell: IVec2,
+    pub alpha: f32,
+    /// Alpha of the [`restore_cue`] drawn over the tile while it's destroyed
+    pub restore_cue: f32,
+    /// Part of the tile texture drawn on this tile, in normalized coordinates
+    pub uv: Rect,
+}
+
+/// Chunk mesh drawing tiles from `origin` to `origin + CHUNK_SIZE`
+#[derive(Component)]
+pub struct TileChunk {
+    origin: IVec2,
+}
+
+#[derive(Resource, Default)]
+pub struct TileChunks {
+    chunks: Vec<(IVec2, Entity)>,
+    /// Shared by all chunks, texture is set by [`autotile`]
+    material: Handle<ColorMaterial>,
+    /// Rebuild every chunk, for example after the tiles are set up
+    all_dirty: bool,
+}
+
+impl TileChunks {
+    #[inline]
+    pub fn chunk_of(cell: IVec2) -> IVec2 {
+        cell.div_euclid(IVec2::splat(CHUNK_SIZE as i32))
+    }
+
+    #[inline]
+    pub fn mark_all_dirty(&mut self) {
+        self.all_dirty = true;
+    }
+}
+
+pub type TileFadeAwayAnimation = Animation<TileFadeAway, Disable<TileFadeAway>>;
+pub type TileShowUpAnimation = Animation<TileShowUp, Disable<TileShowUp>>;
+
+/// [`FadeAway`](crate::common::animation::fade_away::FadeAway) for [`TileVisual`]
+pub struct TileFadeAway;
+
+impl EntitySystem for TileFadeAway {
+    type Data = &'static mut TileVisual;
+    type Filter = ();
+    type Param = ();
+
+    type In = f32;
+    type Out = ();
+
+    fn run(input: Self::In, data: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+        let mut visual = data;
+        visual.alpha = 1. - input;
+    }
+}
+
+/// [`ShowUp`](crate::common::animation::show_up::ShowUp) for [`TileVisual`]
+pub struct TileShowUp;
+
+impl EntitySystem for TileShowUp {
+    type Data = &'static mut TileVisual;
+    type Filter = ();
+    type Param = ();
+
+    type In = f32;
+    type Out = ();
+
+    fn run(input: Self::In, data: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+        let mut visual = data;
+        visual.alpha = input;
+    }
+}
+
+/// Spawns [`TileVisual`]s of the alive tiles and the chunks, public for the `land_tiles` bench
+pub fn setup_tiles(
+    mut commands: Commands,
+    mut tiles: ResMut<LandTiles>,
+    mut chunks: ResMut<TileChunks>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tuning: Res<Tuning>,
+) {
+    let fall_duration = Duration::from_secs_f32(tuning.tiles.fall_animation_duration);
+    let restore_duration = Duration::from_secs_f32(tuning.tiles.restore_animation_duration);
+
+    // Void cells are never drawn
+    let cells: Vec<IVec2> = tiles
+        .cells()
+        .filter(|(_, tile)| !matches!(tile, LandTile::Void))
+        .map(|(cell, _)| cell)
+        .collect();
+
+    for &cell in &cells {
+        let visual = commands.spawn((
+            TileVisual {
+                cell,
+                alpha: 1.,
+                restore_cue: 0.,
+                uv: Rect::new(0., 0., 1., 1.),
+            },
+            TileFadeAwayAnimation::new_disabled(Timer::new(fall_duration, TimerMode::Once)),
+            TileShowUpAnimation::new_disabled(Timer::new(restore_duration, TimerMode::Once)),
+            // Duration is set when the tile is destroyed
+            TileRestoreCueAnimation::new_disabled(Timer::default()),
+        ));
+
+        tiles.set_visual(cell, visual.id());
+    }
+
+    chunks.material = materials.add(ColorMaterial::default());
+
+    let chunk_origins: HashSet<IVec2> = cells
+        .iter()
+        .map(|&cell| TileChunks::chunk_of(cell) * CHUNK_SIZE as i32)
+        .collect();
+
+    chunks.chunks.clear();
+    for origin in chunk_origins {
+        let chunk = commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(empty_chunk_mesh())),
+                material: chunks.material.clone(),
+                transform: Transform::from_xyz(0., 0., TILES_Z),
+                ..default()
+            },
+            TileChunk { origin },
+        ));
+
+        chunks
+            .chunks
+            .push((TileChunks::chunk_of(origin), chunk.id()));
+    }
+    chunks.mark_all_dirty();
+}
+
+fn apply_tilemap_tuning(
+    tuning: Res<Tuning>,
+    mut query: Query<(&mut TileFadeAwayAnimation, &mut TileShowUpAnimation)>,
+) {
+    let fall_duration = Duration::from_secs_f32(tuning.tiles.fall_animation_duration);
+    let restore_duration = Duration::from_secs_f32(tuning.tiles.restore_animation_duration);
+
+    for (mut fade_animation, mut show_animation) in query.iter_mut() {
+        fade_animation.set_duration(fall_duration);
+        show_animation.set_duration(restore_duration);
+    }
+}
+
+fn empty_chunk_mesh() -> Mesh {
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Public for the `land_tiles` bench
+pub fn rebuild_dirty_chunks(
+    mut chunks: ResMut<TileChunks>,
+    tiles: Res<LandTiles>,
+    changed: Query<&TileVisual, Changed<TileVisual>>,
+    visuals: Query<&TileVisual>,
+    chunk_query: Query<(&TileChunk, &Mesh2dHandle)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let dirty: HashSet<IVec2> = if chunks.all_dirty {
+        chunks.chunks.iter().map(|&(chunk, _)| chunk).collect()
+    } else {
+        changed
+            .iter()
+            .map(|visual| TileChunks::chunk_of(visual.cell))
+            .collect()
+    };
+    chunks.all_dirty = false;
+
+    for &(chunk, entity) in &chunks.chunks {
+        if !dirty.contains(&chunk) {
+            continue;
+        }
+        let Ok((chunk, Mesh2dHandle(mesh))) = chunk_query.get(entity) else {
+            continue;
+        };
+
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut colors = Vec::new();
+        let mut indices = Vec::new();
+
+        let half_size = tiles.tile_size() / 2.;
+
+        for x in 0..CHUNK_SIZE as i32 {
+            for y in 0..CHUNK_SIZE as i32 {
+                let cell = chunk.origin + IVec2::new(x, y);
+                let Some(visual) = tiles
+                    .visual(cell)
+                    .and_then(|entity| visuals.get(entity).ok())
+                else {
+                    continue;
+                };
+
+                let center = tiles.array_to_world(cell);
+                let color = if visual.restore_cue > visual.alpha {
+                    RESTORE_CUE_COLOR.with_alpha(visual.restore_cue)
+                } else {
+                    Color::WHITE.with_alpha(visual.alpha)
+                }
+                .to_linear()
+                .to_f32_array();
+                let start = positions.len() as u32;
+
+                // Texture v grows downwards
+                for (corner, uv) in [
+                    (
+                        Vec2::new(-1., -1.),
+                        Vec2::new(visual.uv.min.x, visual.uv.max.y),
+                    ),
+                    (
+                        Vec2::new(1., -1.),
+                        Vec2::new(visual.uv.max.x, visual.uv.max.y),
+                    ),
+                    (
+                        Vec2::new(1., 1.),
+                        Vec2::new(visual.uv.max.x, visual.uv.min.y),
+                    ),
+                    (
+                        Vec2::new(-1., 1.),
+                        Vec2::new(visual.uv.min.x, visual.uv.min.y),
+                    ),
+                ] {
+                    positions.push((center + corner * half_size).extend(0.).to_array());
+                    uvs.push(uv.to_array());
+                    colors.push(color);
+                }
+
+                indices.extend([start, start + 1, start + 2, start, start + 2, start + 3]);
+            }
+        }
+
+        let mesh_data = empty_chunk_mesh()
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+            .with_inserted_indices(Indices::U32(indices));
+
+        meshes.insert(mesh, mesh_data);
+    }
+}
+
+fn cleanup_tiles(
+    mut commands: Commands,
+    mut chunks: ResMut<TileChunks>,
+    visuals: Query<Entity, Or<(With<TileVisual>, With<TileChunk>)>>,
+) {
+    for entity in visuals.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    chunks.chunks.clear();
+}
@@ -0,0 +1,74 @@
+//! Shows when a destroyed tile will be restored.
+//!
+//! A tinted ghost of the tile fades in while it's destroyed and flashes shortly before it's
+//! restored. Driven by [`TileRestoreCueAnimation`] with the duration of the restore timer.
+
+use bevy::prelude::*;
+
+use super::TileVisual;
+use crate::{
+    common::animation::Animation,
+    dynamic_initialization::{DataItem, EntitySystem, ParamItem},
+};
+
+/// Alpha of the ghost right before the warning
+const MAX_ALPHA: f32 = 0.35;
+/// Fraction of the restore time after which the ghost flashes
+const WARNING_FRACTION: f32 = 0.8;
+const WARNING_FLASHES: f32 = 3.;
+const FLASH_ALPHA: f32 = 0.8;
+
+/// Tint of the ghost
+pub const RESTORE_CUE_COLOR: Color = Color::srgb(0.7, 0.85, 1.);
+
+pub type TileRestoreCueAnimation = Animation<TileRestoreCue, HideTileRestoreCue>;
+
+pub struct TileRestoreCue;
+
+impl EntitySystem for TileRestoreCue {
+    type Data = &'static mut TileVisual;
+    type Filter = ();
+    type Param = ();
+
+    type In = f32;
+    type Out = ();
+
+    fn run(input: Self::In, data: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+        let mut visual = data;
+
+        visual.restore_cue = if input < WARNING_FRACTION {
+            input / WARNING_FRACTION * MAX_ALPHA
+        } else {
+            let warning = (input - WARNING_FRACTION) / (1. - WARNING_FRACTION);
+            let flash_on = ((warning * WARNING_FLASHES * 2.) as u32).is_multiple_of(2);
+
+            if flash_on {
+                FLASH_ALPHA
+            } else {
+                MAX_ALPHA
+            }
+        };
+    }
+}
+
+/// Disables the cue once the tile is restored
+pub struct HideTileRestoreCue;
+
+impl EntitySystem for HideTileRestoreCue {
+    type Data = (
+        &'static mut TileRestoreCueAnimation,
+        &'static mut TileVisual,
+    );
+    type Filter = ();
+    type Param = ();
+
+    type In = ();
+    type Out = ();
+
+    fn run(_: Self::In, data: DataItem<'_, Self>, _: ParamItem<'_, '_, Self>) {
+        let (mut animation, mut visual) = data;
+
+        animation.disable();
+        visual.restore_cue = 0.;
+    }
+}
@@ -0,0 +1,169 @@
+//! Gameplay values loaded from `assets/game.tuning.ron`.
+//!
+//! The file is hot reloaded when the `dev` feature is enabled,
+//! the [`Tuning`] resource always holds the latest loaded values.
+//! Values read on spawn are applied to existing entities by systems running on [`Tuning`] change.
+
+use bevy::prelude::*;
+
+use crate::{
+    action_behaviour::actions::movement::MovementParams,
+    common::reflect_ron::{ReflectRonApp, ReflectRonAsset},
+    playing_state::CaughtByRestoreRule,
+};
+
+const TUNING_PATH: &str = "game.tuning.ron";
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_reflect_ron_asset::<Tuning>()
+            .init_resource::<Tuning>()
+            .add_systems(Startup, load_tuning)
+            .add_systems(PreUpdate, update_tuning);
+    }
+}
+
+/// Durations are in seconds
+#[derive(Asset, Resource, Reflect, Clone)]
+pub struct Tuning {
+    pub player: PlayerTuning,
+    pub dasher: DasherTuning,
+    pub tiles: TilesTuning,
+    pub spawn: SpawnTuning,
+}
+
+impl ReflectRonAsset for Tuning {
+    const EXTENSIONS: &'static [&'static str] = &["tuning.ron"];
+}
+
+#[derive(Reflect, Clone)]
+pub struct PlayerTuning {
+    pub max_speed: f32,
+    pub ground: MovementParams,
+    pub airborne: MovementParams,
+    /// See [`Weight`](crate::common::hits::Weight)
+    pub weight: f32,
+    /// How long destroyed tile stays destroyed
+    pub remove_tile_duration: f32,
+    /// Destroying a tile knocks enemies around the player away
+    pub stomp_radius: f32,
+    pub stomp_lifetime: f32,
+    pub stomp_hitbox_lifetime: f32,
+    pub stomp_knockback_strength: f32,
+    pub stomp_knockback_duration: f32,
+}
+
+#[derive(Reflect, Clone)]
+pub struct DasherTuning {
+    pub attack_period: f32,
+    pub attack_radius: f32,
+    pub attack_lifetime: f32,
+    pub attack_hitbox_lifetime: f32,
+    /// Attack disappears when it touches a wall
+    pub attack_stopped_by_walls: bool,
+    pub impact_trauma: f32,
+    pub knockback_strength: f32,
+    pub knockback_duration: f32,
+}
+
+#[derive(Reflect, Clone)]
+pub struct TilesTuning {
+    /// Tile size in pixels, applied when a run starts
+    pub tile_size: f32,
+    pub fall_animation_duration: f32,
+    pub restore_animation_duration: f32,
+    /// What happens to characters inside a hole when the tile is restored
+    pub caught_by_restore: CaughtByRestoreRule,
+}
+
+/// See [`spawn_telegraph`](crate::actors::spawn_telegraph)
+#[derive(Reflect, Clone)]
+pub struct SpawnTuning {
+    pub telegraph_duration: f32,
+    /// Actors aren't spawned closer to the player
+    pub min_player_distance: f32,
+    /// Requested positions are moved randomly up to this distance,
+    /// so actors requested at the same position don't stack
+    pub scatter: f32,
+}
+
+// Used until the file is loaded, keep in sync with `assets/game.tuning.ron`
+impl Default for Tuning {
+    fn default() -> Self {
+        Self {
+            player: PlayerTuning {
+                max_speed: 100.0,
+                ground: MovementParams {
+                    acceleration: 1500.0,
+                    deceleration: 2500.0,
+                    friction: 1200.0,
+                },
+                airborne: MovementParams {
+                    acceleration: 300.0,
+                    deceleration: 300.0,
+                    friction: 100.0,
+                },
+                weight: 1.0,
+                remove_tile_duration: 2.0,
+                stomp_radius: 30.0,
+                stomp_lifetime: 0.3,
+                stomp_hitbox_lifetime: 0.075,
+                stomp_knockback_strength: 300.0,
+                stomp_knockback_duration: 0.3,
+            },
+            dasher: DasherTuning {
+                attack_period: 3.0,
+                attack_radius: 20.0,
+                attack_lifetime: 0.5,
+                attack_hitbox_lifetime: 0.075,
+                attack_stopped_by_walls: true,
+                impact_trauma: 0.3,
+                knockback_strength: 150.0,
+                knockback_duration: 0.25,
+            },
+            tiles: TilesTuning {
+                tile_size: 40.0,
+                fall_animation_duration: 0.3,
+                restore_animation_duration: 0.15,
+                caught_by_restore: CaughtByRestoreRule::Eject,
+            },
+            spawn: SpawnTuning {
+                telegraph_duration: 0.6,
+                min_player_distance: 32.0,
+                scatter: 12.0,
+            },
+        }
+    }
+}
+
+#[derive(Resource)]
+struct TuningHandle(Handle<Tuning>);
+
+fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load(TUNING_PATH)));
+}
+
+fn update_tuning(
+    mut events: EventReader<AssetEvent<Tuning>>,
+    handle: Res<TuningHandle>,
+    assets: Res<Assets<Tuning>>,
+    mut tuning: ResMut<Tuning>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+
+        if id != handle.0.id() {
+            continue;
+        }
+
+        if let Some(loaded) = assets.get(id) {
+            info!("Tuning loaded");
+            *tuning = loaded.clone();
+        }
+    }
+}